@@ -2,16 +2,48 @@
 
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::punctuated::Punctuated;
 use syn::{parse_macro_input, Data, DeriveInput, Expr, ExprLit, Fields, Lit};
 
 /// Derive macro for TomlScaffold trait
-#[proc_macro_derive(TomlScaffold, attributes(format))]
+#[proc_macro_derive(TomlScaffold, attributes(format, scaffold, toml_scaffold))]
 pub fn derive_toml_scaffold(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
 
-    let format_attrs = extract_format_attributes(&input.data);
-    let fields = extract_fields(&input.data);
+    let container = match extract_container_attributes(&input.attrs) {
+        Ok(container) => container,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let migrations = container.migrations.as_ref().map(|path| {
+        quote! {
+            fn migrations() -> ::std::vec::Vec<toml_scaffold::Migration> {
+                #path()
+            }
+        }
+    });
+
+    let format_attrs = match extract_format_attributes(&input, &container) {
+        Ok(format_attrs) => format_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let fields = match extract_fields(&input) {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let scaffold_attrs = match extract_scaffold_attributes(&input) {
+        Ok(scaffold_attrs) => scaffold_attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Bound type parameters used by fields, unless the bounds are given explicitly
+    let mut generics = input.generics.clone();
+    let predicates = match container.bound {
+        Some(bound) => bound,
+        None => infer_bounds(&input.generics, &fields),
+    };
+    generics.make_where_clause().predicates.extend(predicates);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let field_names: Vec<_> = format_attrs.iter().map(|(k, _)| k).collect();
     let format_values: Vec<_> = format_attrs.iter().map(|(_, v)| v).collect();
@@ -19,91 +51,545 @@ pub fn derive_toml_scaffold(input: TokenStream) -> TokenStream {
     let nested_field_names: Vec<_> = fields.iter().map(|(name, _)| name).collect();
     let nested_field_types: Vec<_> = fields.iter().map(|(_, ty)| ty).collect();
 
+    let scaffold_field_names: Vec<_> = scaffold_attrs.iter().map(|(k, _)| k).collect();
+    let scaffold_values: Vec<_> = scaffold_attrs.iter().map(|(_, v)| v.to_tokens()).collect();
+
     let expanded = quote! {
-        impl toml_scaffold::TomlScaffold for #name {
+        impl #impl_generics toml_scaffold::TomlScaffold for #name #ty_generics #where_clause {
             fn format_preferences() -> ::std::collections::HashMap<toml_scaffold::FieldPath, String> {
                 let mut map = ::std::collections::HashMap::new();
 
                 // Direct format attributes
                 #(map.insert(
-                    toml_scaffold::FieldPath::from_vec(vec![#field_names.to_string()]),
+                    toml_scaffold::FieldPath::from_vec(vec![#(#field_names.to_string()),*]),
                     #format_values.to_string()
                 );)*
 
-                // Collect from nested types
+                // Collect from nested types, a format of the type itself only applies
+                // when the field has none
                 #(
                     for (nested_path, nested_value) in <#nested_field_types>::format_preferences() {
-                        let mut full_path = toml_scaffold::FieldPath::from_vec(vec![#nested_field_names.to_string()]);
+                        let mut full_path = toml_scaffold::FieldPath::from_vec(vec![#(#nested_field_names.to_string()),*]);
                         for i in 0..nested_path.len() {
                             if let Some(segment) = nested_path.get(i) {
                                 full_path.push(segment.clone());
                             }
                         }
-                        map.insert(full_path, nested_value);
+                        if nested_path.is_empty() {
+                            map.entry(full_path).or_insert(nested_value);
+                        } else {
+                            map.insert(full_path, nested_value);
+                        }
+                    }
+                )*
+
+                map
+            }
+
+            fn field_attributes() -> ::std::collections::HashMap<toml_scaffold::FieldPath, toml_scaffold::FieldAttributes> {
+                let mut map: ::std::collections::HashMap<_, toml_scaffold::FieldAttributes> =
+                    ::std::collections::HashMap::new();
+
+                // Collect from nested types
+                #(
+                    for (nested_path, nested_value) in <#nested_field_types>::field_attributes() {
+                        let mut full_path = toml_scaffold::FieldPath::from_vec(vec![#(#nested_field_names.to_string()),*]);
+                        for segment in nested_path.segments() {
+                            full_path.push(segment.clone());
+                        }
+                        map.entry(full_path).or_default().merge(nested_value);
                     }
                 )*
 
+                // Direct scaffold attributes override the nested ones
+                #(map.entry(
+                    toml_scaffold::FieldPath::from_vec(vec![#(#scaffold_field_names.to_string()),*])
+                ).or_default().merge(#scaffold_values);)*
+
                 map
             }
+
+            #migrations
         }
     };
 
     TokenStream::from(expanded)
 }
 
-fn extract_format_attributes(data: &Data) -> Vec<(String, String)> {
+/// Attributes declared with `#[toml_scaffold(...)]` on the struct itself
+#[derive(Default)]
+struct ContainerAttributes {
+    /// Function returning the type's migrations
+    migrations: Option<syn::Path>,
+    /// Where-predicates replacing the inferred `TomlScaffold` bounds
+    bound: Option<Vec<syn::WherePredicate>>,
+    /// Default format of the type's own table wherever it is used as a field
+    format: Option<syn::LitStr>,
+}
+
+fn extract_container_attributes(attrs: &[syn::Attribute]) -> syn::Result<ContainerAttributes> {
+    let mut container = ContainerAttributes::default();
+
+    for attr in attrs {
+        if !attr.path().is_ident("toml_scaffold") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("migrations") {
+                // #[toml_scaffold(migrations = "path::to::fn")]
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                container.migrations = Some(lit.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("bound") {
+                // #[toml_scaffold(bound = "T: Trait, U: Other")]
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                let predicates = lit.parse_with(
+                    Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated,
+                )?;
+                container.bound = Some(predicates.into_iter().collect());
+                Ok(())
+            } else if meta.path.is_ident("format") {
+                // #[toml_scaffold(format = "inline")]
+                container.format = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported toml_scaffold attribute"))
+            }
+        })?;
+    }
+
+    Ok(container)
+}
+
+/// Field path segments under which a field's format preferences are recorded.
+///
+/// Named fields use their name; the single field of a newtype struct is transparent
+/// and shares the path of the newtype itself.
+type FieldSegments = Vec<String>;
+
+fn extract_format_attributes(
+    input: &DeriveInput,
+    container: &ContainerAttributes,
+) -> syn::Result<Vec<(FieldSegments, String)>> {
     let mut attrs = Vec::new();
 
-    if let Data::Struct(data_struct) = data {
-        if let Fields::Named(fields) = &data_struct.fields {
-            for field in &fields.named {
-                let field_name = field.ident.as_ref().unwrap().to_string();
+    // #[format = "..."] on a newtype struct formats the newtype itself
+    for attr in &input.attrs {
+        if let Some(format) = parse_format_attribute(attr) {
+            if !is_newtype(&input.data) {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "#[format] on a struct is only supported for newtype structs, use #[toml_scaffold(format = \"...\")]",
+                ));
+            }
+            attrs.push((Vec::new(), format));
+        }
+    }
 
-                for attr in &field.attrs {
-                    if !attr.path().is_ident("format") {
-                        continue;
-                    }
+    // #[toml_scaffold(format = "...")] formats the struct's own table, parents
+    // inherit it for fields of this type
+    if let Some(lit) = &container.format {
+        if is_newtype(&input.data) {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "use #[format = \"...\"] to format a newtype struct",
+            ));
+        }
+        attrs.push((Vec::new(), lit.value()));
+    }
 
-                    // Try parsing as name-value: #[format = "value"]
-                    if let syn::Meta::NameValue(nv) = &attr.meta {
-                        if let Expr::Lit(ExprLit {
-                            lit: Lit::Str(lit_str),
-                            ..
-                        }) = &nv.value
-                        {
-                            attrs.push((field_name.clone(), lit_str.value()));
-                            continue;
-                        }
-                    }
+    for (segments, field) in transparent_fields(input)? {
+        for attr in &field.attrs {
+            if let Some(format) = parse_format_attribute(attr) {
+                attrs.push((segments.clone(), format));
+            }
+        }
+    }
 
-                    // Try parsing as function-like: #[format("value")]
-                    if let Ok(Expr::Lit(ExprLit {
-                        lit: Lit::Str(lit_str),
-                        ..
-                    })) = attr.parse_args()
-                    {
-                        attrs.push((field_name.clone(), lit_str.value()));
-                    }
+    Ok(attrs)
+}
+
+/// Settings declared with `#[scaffold(...)]` on a field
+#[derive(Default)]
+struct ScaffoldAttributes {
+    /// Comment replacing the doc comment
+    doc: Option<String>,
+    /// Leave the field out of scaffolds
+    hidden: bool,
+    /// Render the field commented out
+    commented: bool,
+    /// Place single-line docs of the field, or every key in its section, after the value
+    trailing_comments: bool,
+    /// Line up `=` signs of the field's section
+    aligned: bool,
+    /// Group of keys the field belongs to
+    group: Option<String>,
+    /// Priority among the keys of the table, an `i64` expression
+    order: Option<Expr>,
+    /// Deprecation note from `#[deprecated]` or `#[scaffold(deprecated = "...")]`
+    deprecated: Option<String>,
+    /// Placeholder value, any expression convertible into `toml::Value`
+    example: Option<Expr>,
+}
+
+impl ScaffoldAttributes {
+    /// Build the equivalent `toml_scaffold::FieldAttributes` expression
+    fn to_tokens(&self) -> proc_macro2::TokenStream {
+        let doc = self
+            .doc
+            .as_ref()
+            .map(|doc| quote!(attributes.doc = ::std::option::Option::Some(#doc.to_string());));
+        let hidden = self.hidden.then(|| quote!(attributes.hidden = true;));
+        let commented = self.commented.then(|| quote!(attributes.commented = true;));
+        let trailing_comments = self
+            .trailing_comments
+            .then(|| quote!(attributes.trailing_comments = true;));
+        let aligned = self.aligned.then(|| quote!(attributes.aligned = true;));
+        let group = self.group.as_ref().map(
+            |group| quote!(attributes.group = ::std::option::Option::Some(#group.to_string());),
+        );
+        let order = self
+            .order
+            .as_ref()
+            .map(|order| quote!(attributes.order = ::std::option::Option::Some(#order);));
+        let deprecated = self.deprecated.as_ref().map(
+            |note| quote!(attributes.deprecated = ::std::option::Option::Some(#note.to_string());),
+        );
+        let example = self.example.as_ref().map(|example| {
+            // Array literals become vectors, which convert into TOML arrays
+            let example = match example {
+                Expr::Array(array) => {
+                    let elems = &array.elems;
+                    quote!(::std::vec![#elems])
                 }
+                other => quote!(#other),
+            };
+            quote!(attributes = attributes.with_example(#example);)
+        });
+        quote! {
+            {
+                let mut attributes = toml_scaffold::FieldAttributes::default();
+                #doc
+                #hidden
+                #commented
+                #trailing_comments
+                #aligned
+                #group
+                #order
+                #deprecated
+                #example
+                attributes
             }
         }
     }
+}
 
-    attrs
+fn extract_scaffold_attributes(
+    input: &DeriveInput,
+) -> syn::Result<Vec<(FieldSegments, ScaffoldAttributes)>> {
+    let mut attrs = Vec::new();
+    let container_default = has_serde_default(&input.attrs)?;
+
+    for (segments, field) in transparent_fields(input)? {
+        let mut scaffold = ScaffoldAttributes::default();
+        let mut found = false;
+
+        for attr in &field.attrs {
+            if attr.path().is_ident("deprecated") {
+                // Bare #[deprecated] is already reported by the JSON schema
+                if let Some(note) = parse_deprecated_note(attr)? {
+                    scaffold.deprecated = Some(note);
+                    found = true;
+                }
+                continue;
+            }
+            if !attr.path().is_ident("scaffold") {
+                continue;
+            }
+            found = true;
+
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("doc") {
+                    // #[scaffold(doc = "...")]
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    scaffold.doc = Some(lit.value());
+                    Ok(())
+                } else if meta.path.is_ident("hidden") {
+                    // #[scaffold(hidden)]
+                    scaffold.hidden = true;
+                    Ok(())
+                } else if meta.path.is_ident("commented") {
+                    // #[scaffold(commented)]
+                    scaffold.commented = true;
+                    Ok(())
+                } else if meta.path.is_ident("trailing_comments") {
+                    // #[scaffold(trailing_comments)]
+                    scaffold.trailing_comments = true;
+                    Ok(())
+                } else if meta.path.is_ident("aligned") {
+                    // #[scaffold(aligned)]
+                    scaffold.aligned = true;
+                    Ok(())
+                } else if meta.path.is_ident("order") {
+                    // #[scaffold(order = -1)]
+                    scaffold.order = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("group") {
+                    // #[scaffold(group = "Networking")]
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    scaffold.group = Some(lit.value());
+                    Ok(())
+                } else if meta.path.is_ident("example") {
+                    // #[scaffold(example = 8080)] or #[scaffold(example = ["a", "b"])]
+                    scaffold.example = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("deprecated") {
+                    // #[scaffold(deprecated = "use `addr` instead")]
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    scaffold.deprecated = Some(lit.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported scaffold attribute"))
+                }
+            })?;
+        }
+
+        // A field left out of the scaffold, or commented out, must be optional for the
+        // scaffold to deserialize. Newtype fields depend on the parent and are not checked
+        if (scaffold.hidden || scaffold.commented)
+            && field.ident.is_some()
+            && !container_default
+            && !is_option(&field.ty)
+            && !has_serde_default(&field.attrs)?
+        {
+            let attr = if scaffold.hidden {
+                "hidden"
+            } else {
+                "commented"
+            };
+            return Err(syn::Error::new_spanned(
+                field,
+                format!(
+                    "#[scaffold({})] requires an `Option` field or #[serde(default)], the scaffold would not deserialize otherwise",
+                    attr
+                ),
+            ));
+        }
+
+        if found {
+            attrs.push((segments, scaffold));
+        }
+    }
+
+    Ok(attrs)
+}
+
+/// Whether `#[serde(default)]`, `#[serde(default = "...")]` or `#[serde(skip)]` lets
+/// serde fill in a missing value
+fn has_serde_default(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let fills_missing = |meta: &syn::Meta| {
+        ["default", "skip", "skip_deserializing"]
+            .iter()
+            .any(|name| meta.path().is_ident(name))
+    };
+    Ok(serde_metas(attrs)?.iter().any(fills_missing))
 }
 
-fn extract_fields(data: &Data) -> Vec<(String, syn::Type)> {
-    let mut fields = Vec::new();
+/// Items of every `#[serde(...)]` attribute
+fn serde_metas(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn::Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            metas.extend(
+                attr.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)?,
+            );
+        }
+    }
+    Ok(metas)
+}
 
-    if let Data::Struct(data_struct) = data {
-        if let Fields::Named(named_fields) = &data_struct.fields {
-            for field in &named_fields.named {
-                let field_name = field.ident.as_ref().unwrap().to_string();
-                let field_type = field.ty.clone();
-                fields.push((field_name, field_type));
+/// The serialized value of `name = "..."` or `name(serialize = "...")` among serde items
+fn serde_name(metas: &[syn::Meta], name: &str) -> syn::Result<Option<String>> {
+    for meta in metas.iter().filter(|meta| meta.path().is_ident(name)) {
+        match meta {
+            syn::Meta::NameValue(nv) => {
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) = &nv.value
+                {
+                    return Ok(Some(lit.value()));
+                }
             }
+            syn::Meta::List(list) => {
+                let nested = list
+                    .parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)?;
+                if let Some(value) =
+                    serde_name(&nested.into_iter().collect::<Vec<_>>(), "serialize")?
+                {
+                    return Ok(Some(value));
+                }
+            }
+            syn::Meta::Path(_) => {}
         }
     }
+    Ok(None)
+}
+
+/// The key a named field serializes to, from `#[serde(rename)]` or the container's
+/// `#[serde(rename_all)]`, the same way serde and schemars name it
+fn serialized_name(field: &syn::Field, rename_all: Option<&str>) -> syn::Result<String> {
+    if let Some(name) = serde_name(&serde_metas(&field.attrs)?, "rename")? {
+        return Ok(name);
+    }
+    let ident = field.ident.as_ref().unwrap().to_string();
+    let ident = ident.strip_prefix("r#").unwrap_or(&ident).to_string();
+    let pascal = || {
+        ident
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect::<String>()
+    };
+    Ok(match rename_all {
+        Some("UPPERCASE" | "SCREAMING_SNAKE_CASE") => ident.to_ascii_uppercase(),
+        Some("PascalCase") => pascal(),
+        Some("camelCase") => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        }
+        Some("kebab-case") => ident.replace('_', "-"),
+        Some("SCREAMING-KEBAB-CASE") => ident.to_ascii_uppercase().replace('_', "-"),
+        _ => ident,
+    })
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "Option"))
+}
+
+/// Read the note of `#[deprecated = "note"]` or `#[deprecated(note = "note")]`
+fn parse_deprecated_note(attr: &syn::Attribute) -> syn::Result<Option<String>> {
+    match &attr.meta {
+        syn::Meta::Path(_) => Ok(None),
+        syn::Meta::NameValue(nv) => match &nv.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) => Ok(Some(lit_str.value())),
+            _ => Ok(None),
+        },
+        syn::Meta::List(_) => {
+            let mut note = None;
+            attr.parse_nested_meta(|meta| {
+                let lit: syn::LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("note") {
+                    note = Some(lit.value());
+                }
+                Ok(())
+            })?;
+            Ok(note)
+        }
+    }
+}
+
+/// Parse `#[format = "value"]` or `#[format("value")]`
+fn parse_format_attribute(attr: &syn::Attribute) -> Option<String> {
+    if !attr.path().is_ident("format") {
+        return None;
+    }
+
+    // Try parsing as name-value: #[format = "value"]
+    if let syn::Meta::NameValue(nv) = &attr.meta {
+        if let Expr::Lit(ExprLit {
+            lit: Lit::Str(lit_str),
+            ..
+        }) = &nv.value
+        {
+            return Some(lit_str.value());
+        }
+    }
+
+    // Try parsing as function-like: #[format("value")]
+    if let Ok(Expr::Lit(ExprLit {
+        lit: Lit::Str(lit_str),
+        ..
+    })) = attr.parse_args()
+    {
+        return Some(lit_str.value());
+    }
+
+    None
+}
+
+fn extract_fields(input: &DeriveInput) -> syn::Result<Vec<(FieldSegments, syn::Type)>> {
+    Ok(transparent_fields(input)?
+        .into_iter()
+        .map(|(segments, field)| (segments, field.ty.clone()))
+        .collect())
+}
+
+/// Fields whose format preferences propagate, with the path segments they live under
+fn transparent_fields(input: &DeriveInput) -> syn::Result<Vec<(FieldSegments, &syn::Field)>> {
+    let Data::Struct(data_struct) = &input.data else {
+        return Ok(Vec::new());
+    };
+
+    match &data_struct.fields {
+        Fields::Named(named_fields) => {
+            let rename_all = serde_name(&serde_metas(&input.attrs)?, "rename_all")?;
+            named_fields
+                .named
+                .iter()
+                .map(|field| Ok((vec![serialized_name(field, rename_all.as_deref())?], field)))
+                .collect()
+        }
+        // Newtypes serialize as their inner value
+        Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => {
+            Ok(vec![(Vec::new(), &unnamed.unnamed[0])])
+        }
+        // Other tuple structs serialize as arrays, whose elements have no field paths
+        _ => Ok(Vec::new()),
+    }
+}
+
+fn is_newtype(data: &Data) -> bool {
+    matches!(data, Data::Struct(s) if matches!(&s.fields, Fields::Unnamed(f) if f.unnamed.len() == 1))
+}
+
+/// Require `TomlScaffold` for every type parameter that appears in a field type
+fn infer_bounds(
+    generics: &syn::Generics,
+    fields: &[(FieldSegments, syn::Type)],
+) -> Vec<syn::WherePredicate> {
+    generics
+        .type_params()
+        .filter(|param| {
+            fields
+                .iter()
+                .any(|(_, ty)| mentions_ident(quote!(#ty), &param.ident))
+        })
+        .map(|param| {
+            let ident = &param.ident;
+            syn::parse_quote!(#ident: toml_scaffold::TomlScaffold)
+        })
+        .collect()
+}
 
-    fields
+/// Check whether a token stream contains the identifier, including inside groups
+fn mentions_ident(tokens: proc_macro2::TokenStream, ident: &syn::Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Ident(i) => i == *ident,
+        proc_macro2::TokenTree::Group(group) => mentions_ident(group.stream(), ident),
+        _ => false,
+    })
 }
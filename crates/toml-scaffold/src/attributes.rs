@@ -0,0 +1,53 @@
+/// Scaffold-specific settings attached to a field with `#[scaffold(...)]`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldAttributes {
+    /// Comment used instead of the field's doc comment
+    pub doc: Option<String>,
+    /// Leave the field out of scaffolds, it can still be set in config files
+    pub hidden: bool,
+    /// Render the field with its value, but commented out
+    pub commented: bool,
+    /// Place single-line doc comments after the value, for the field or every key in its section
+    pub trailing_comments: bool,
+    /// Line up `=` signs in the field's section and its nested sections
+    pub aligned: bool,
+    /// Group of keys the field belongs to, separated from other groups by an empty line
+    pub group: Option<String>,
+    /// Position among the keys of its table, lower values come first and the default is 0
+    pub order: Option<i64>,
+    /// Deprecation note, marks the field as deprecated
+    pub deprecated: Option<String>,
+    /// Value shown in the placeholder when the field is missing
+    pub example: Option<toml::Value>,
+}
+
+impl FieldAttributes {
+    /// Sets the placeholder example value.
+    pub fn with_example(mut self, value: impl Into<toml::Value>) -> Self {
+        self.example = Some(value.into());
+        self
+    }
+
+    /// Overrides settings with those set in `other`.
+    pub fn merge(&mut self, other: FieldAttributes) {
+        if other.doc.is_some() {
+            self.doc = other.doc;
+        }
+        if other.example.is_some() {
+            self.example = other.example;
+        }
+        if other.deprecated.is_some() {
+            self.deprecated = other.deprecated;
+        }
+        if other.group.is_some() {
+            self.group = other.group;
+        }
+        if other.order.is_some() {
+            self.order = other.order;
+        }
+        self.hidden |= other.hidden;
+        self.commented |= other.commented;
+        self.trailing_comments |= other.trailing_comments;
+        self.aligned |= other.aligned;
+    }
+}
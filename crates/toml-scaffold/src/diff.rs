@@ -0,0 +1,203 @@
+use crate::field_path::FieldPath;
+use crate::format::{append_comment_text, append_section_separator};
+use crate::TomlScaffold;
+
+/// How a value differs between two configurations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Present only in the second configuration
+    Added(toml::Value),
+    /// Present only in the first configuration
+    Removed(toml::Value),
+    /// Present in both with different values
+    Changed {
+        /// Value in the first configuration
+        from: toml::Value,
+        /// Value in the second configuration
+        to: toml::Value,
+    },
+}
+
+/// A single difference between two configurations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    /// Path of the differing value
+    pub path: FieldPath,
+    /// What changed
+    pub change: Change,
+    /// Doc comment of the field, if any
+    pub comment: Option<String>,
+}
+
+/// Differences between two configurations, in document order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfigDiff {
+    /// Differing values; tables are compared key by key, arrays as a whole
+    pub entries: Vec<DiffEntry>,
+}
+
+impl ConfigDiff {
+    /// Returns `true` if both configurations are equal.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Renders a human-readable report grouped by TOML section.
+    ///
+    /// Each entry is preceded by its doc comment and prefixed with `+` (added),
+    /// `-` (removed) or `~` (changed).
+    pub fn render(&self) -> String {
+        // Group entries by their parent table, sections in order of first appearance
+        let mut sections: Vec<(FieldPath, Vec<&DiffEntry>)> = Vec::new();
+        for entry in &self.entries {
+            let section =
+                FieldPath::from_vec(entry.path.segments()[..entry.path.len() - 1].to_vec());
+            match sections.iter_mut().find(|(path, _)| *path == section) {
+                Some((_, entries)) => entries.push(entry),
+                None => sections.push((section, vec![entry])),
+            }
+        }
+        // Root keys have no header, so they must come before any section
+        sections.sort_by_key(|(path, _)| !path.is_empty());
+
+        let mut result = String::new();
+        for (section, entries) in sections {
+            if !section.is_empty() {
+                append_section_separator(&mut result);
+                result.push_str(&format!("[{}]\n", section.as_dotted_key()));
+            }
+            for entry in entries {
+                if let Some(comment) = &entry.comment {
+                    append_comment_text(&mut result, comment, "", "#");
+                }
+                let key = FieldPath::from_vec(vec![entry.path.segments().last().unwrap().clone()]);
+                let line = match &entry.change {
+                    Change::Added(value) => format!("+ {} = {}", key, value),
+                    Change::Removed(value) => format!("- {} = {}", key, value),
+                    Change::Changed { from, to } => format!("~ {} = {} -> {}", key, from, to),
+                };
+                result.push_str(&line);
+                result.push('\n');
+            }
+        }
+
+        result
+    }
+}
+
+impl std::fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+/// Compares two TOML configurations of type `T`, annotating entries with field doc comments.
+pub fn diff<T: TomlScaffold>(a: &str, b: &str) -> Result<ConfigDiff, toml::de::Error> {
+    let a: toml::Table = toml::from_str(a)?;
+    let b: toml::Table = toml::from_str(b)?;
+    let info = T::schema_info();
+
+    let mut entries = Vec::new();
+    diff_tables(&a, &b, &FieldPath::new(), &mut entries);
+    for entry in &mut entries {
+        entry.comment = info.comments.get(&entry.path).cloned();
+    }
+    Ok(ConfigDiff { entries })
+}
+
+/// Compare two tables key by key, keys of `a` first
+fn diff_tables(a: &toml::Table, b: &toml::Table, path: &FieldPath, entries: &mut Vec<DiffEntry>) {
+    for (key, a_val) in a {
+        let current_path = path.child(key.clone());
+        match b.get(key) {
+            Some(b_val) => diff_values(a_val, b_val, &current_path, entries),
+            None => push_leaves(a_val, &current_path, entries, Change::Removed),
+        }
+    }
+    for (key, b_val) in b {
+        if !a.contains_key(key) {
+            push_leaves(b_val, &path.child(key.clone()), entries, Change::Added);
+        }
+    }
+}
+
+/// Compare two values, recursing into tables present on both sides
+fn diff_values(a: &toml::Value, b: &toml::Value, path: &FieldPath, entries: &mut Vec<DiffEntry>) {
+    match (a, b) {
+        (toml::Value::Table(a), toml::Value::Table(b)) => diff_tables(a, b, path, entries),
+        _ if a != b => entries.push(DiffEntry {
+            path: path.clone(),
+            change: Change::Changed {
+                from: a.clone(),
+                to: b.clone(),
+            },
+            comment: None,
+        }),
+        _ => {}
+    }
+}
+
+/// Record every leaf of a one-sided value as added or removed
+fn push_leaves(
+    value: &toml::Value,
+    path: &FieldPath,
+    entries: &mut Vec<DiffEntry>,
+    change: fn(toml::Value) -> Change,
+) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (key, val) in table {
+                push_leaves(val, &path.child(key.clone()), entries, change);
+            }
+        }
+        _ => entries.push(DiffEntry {
+            path: path.clone(),
+            change: change(value.clone()),
+            comment: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(a: &str, b: &str) -> Vec<DiffEntry> {
+        let mut entries = Vec::new();
+        diff_tables(
+            &toml::from_str(a).unwrap(),
+            &toml::from_str(b).unwrap(),
+            &FieldPath::new(),
+            &mut entries,
+        );
+        entries
+    }
+
+    #[test]
+    fn test_diff_equal() {
+        assert!(entries("a = 1\n[t]\nb = [1, 2]", "a = 1\n[t]\nb = [1, 2]").is_empty());
+    }
+
+    #[test]
+    fn test_diff_changed_added_removed() {
+        let entries = entries("a = 1\nb = 2", "a = 3\nc = 4");
+        let paths: Vec<String> = entries.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["a", "b", "c"]);
+        assert_eq!(
+            entries[0].change,
+            Change::Changed {
+                from: 1.into(),
+                to: 3.into()
+            }
+        );
+        assert_eq!(entries[1].change, Change::Removed(2.into()));
+        assert_eq!(entries[2].change, Change::Added(4.into()));
+    }
+
+    #[test]
+    fn test_diff_one_sided_table_expands_to_leaves() {
+        let entries = entries("", "[t]\nx = 1\ny = 2");
+        let paths: Vec<String> = entries.iter().map(|e| e.path.to_string()).collect();
+        assert_eq!(paths, ["t.x", "t.y"]);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::TomlScaffold;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that rewrites out-of-date scaffold files instead of failing.
+pub const BLESS_ENV_VAR: &str = "TOML_SCAFFOLD_BLESS";
+
+/// Number of unchanged lines shown around each change in a diff
+const CONTEXT_LINES: usize = 3;
+
+/// Error returned by [`check_scaffold`].
+#[derive(Debug)]
+pub enum DriftError {
+    /// The value could not be serialized to TOML
+    Serialize(toml::ser::Error),
+    /// The scaffold file could not be read or written
+    Io {
+        /// Path of the scaffold file
+        path: PathBuf,
+        /// Underlying I/O error
+        error: std::io::Error,
+    },
+    /// The scaffold file does not match the regenerated scaffold
+    OutOfDate {
+        /// Path of the scaffold file
+        path: PathBuf,
+        /// Unified diff from the file to the regenerated scaffold
+        diff: String,
+    },
+}
+
+impl std::fmt::Display for DriftError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriftError::Serialize(error) => write!(f, "failed to generate scaffold: {}", error),
+            DriftError::Io { path, error } => {
+                write!(f, "failed to access {}: {}", path.display(), error)
+            }
+            DriftError::OutOfDate { path, diff } => write!(
+                f,
+                "scaffold {} is out of date, rerun with {}=1 to update it\n\n{}",
+                path.display(),
+                BLESS_ENV_VAR,
+                diff
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DriftError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DriftError::Serialize(error) => Some(error),
+            DriftError::Io { error, .. } => Some(error),
+            DriftError::OutOfDate { .. } => None,
+        }
+    }
+}
+
+/// Regenerates the scaffold for `value` and compares it with the file at `path`.
+///
+/// When the `TOML_SCAFFOLD_BLESS` environment variable is set to anything but `0`,
+/// the file is rewritten instead of reporting a mismatch.
+pub fn check_scaffold<T: TomlScaffold>(
+    value: &T,
+    path: impl AsRef<Path>,
+) -> Result<(), DriftError> {
+    let generated = value.to_scaffold().map_err(DriftError::Serialize)?;
+    let bless = std::env::var(BLESS_ENV_VAR).is_ok_and(|v| !v.is_empty() && v != "0");
+    check_or_bless(&generated, path.as_ref(), bless)
+}
+
+/// Compare `generated` with the file, or overwrite the file when blessing
+fn check_or_bless(generated: &str, path: &Path, bless: bool) -> Result<(), DriftError> {
+    let io_error = |error| DriftError::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+
+    let current = match std::fs::read_to_string(path) {
+        Ok(current) => current,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(io_error(error)),
+    };
+    if current == generated {
+        return Ok(());
+    }
+
+    if bless {
+        return std::fs::write(path, generated).map_err(io_error);
+    }
+
+    let name = path.display().to_string();
+    Err(DriftError::OutOfDate {
+        path: path.to_path_buf(),
+        diff: unified_diff(&current, generated, &name, &format!("{} (generated)", name)),
+    })
+}
+
+/// Asserts that a checked-in scaffold file matches the scaffold generated from a value.
+///
+/// Relative paths are resolved against the crate's `CARGO_MANIFEST_DIR`. On mismatch the
+/// assertion panics with a unified diff; set `TOML_SCAFFOLD_BLESS=1` to rewrite the file.
+///
+/// ```ignore
+/// #[test]
+/// fn example_config_is_up_to_date() {
+///     toml_scaffold::assert_scaffold_up_to_date!(Config::default(), "config.example.toml");
+/// }
+/// ```
+#[macro_export]
+macro_rules! assert_scaffold_up_to_date {
+    ($value:expr, $path:expr $(,)?) => {
+        if let Err(error) = $crate::check_scaffold(
+            &$value,
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        ) {
+            panic!("{}", error);
+        }
+    };
+}
+
+/// Line-level edit operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Render a unified diff between two texts
+fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = diff_lines(&old_lines, &new_lines);
+
+    let mut result = format!("--- {}\n+++ {}\n", old_name, new_name);
+    let changes: Vec<usize> = (0..edits.len())
+        .filter(|&i| !matches!(edits[i], Edit::Equal(..)))
+        .collect();
+
+    let mut i = 0;
+    while i < changes.len() {
+        // Extend the hunk while the next change is close enough to share context
+        let start = changes[i].saturating_sub(CONTEXT_LINES);
+        let mut end = changes[i];
+        while i + 1 < changes.len() && changes[i + 1] <= end + 2 * CONTEXT_LINES + 1 {
+            i += 1;
+            end = changes[i];
+        }
+        let end = (end + CONTEXT_LINES + 1).min(edits.len());
+        i += 1;
+
+        let hunk = &edits[start..end];
+        let (old_start, new_start) = hunk_start(&edits[..start]);
+        let old_count = hunk
+            .iter()
+            .filter(|e| !matches!(e, Edit::Insert(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|e| !matches!(e, Edit::Delete(_)))
+            .count();
+        result.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for edit in hunk {
+            match *edit {
+                Edit::Equal(o, _) => result.push_str(&format!(" {}\n", old_lines[o])),
+                Edit::Delete(o) => result.push_str(&format!("-{}\n", old_lines[o])),
+                Edit::Insert(n) => result.push_str(&format!("+{}\n", new_lines[n])),
+            }
+        }
+    }
+
+    result
+}
+
+/// Number of old and new lines consumed before a hunk
+fn hunk_start(edits: &[Edit]) -> (usize, usize) {
+    edits.iter().fold((0, 0), |(old, new), edit| match edit {
+        Edit::Equal(..) => (old + 1, new + 1),
+        Edit::Delete(_) => (old + 1, new),
+        Edit::Insert(_) => (old, new + 1),
+    })
+}
+
+/// Format a hunk range as `start,count` (1-based, empty ranges point at the previous line)
+fn hunk_range(consumed: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", consumed)
+    } else {
+        format!("{},{}", consumed + 1, count)
+    }
+}
+
+/// Compute a minimal line edit script using the longest common subsequence
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    // lcs[i][j] is the LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            edits.push(Edit::Equal(i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            edits.push(Edit::Delete(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Insert(j));
+            j += 1;
+        }
+    }
+    edits.extend((i..old.len()).map(Edit::Delete));
+    edits.extend((j..new.len()).map(Edit::Insert));
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\n";
+        let new = "a\nB\nc\nd\ne\n";
+        assert_eq!(
+            unified_diff(old, new, "old", "new"),
+            "--- old\n+++ new\n@@ -1,4 +1,5 @@\n a\n-b\n+B\n c\n d\n+e\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_separate_hunks() {
+        let old: String = (1..=20).map(|i| format!("{}\n", i)).collect();
+        let new: String = (1..=20)
+            .map(|i| match i {
+                2 => "two\n".to_string(),
+                19 => "nineteen\n".to_string(),
+                _ => format!("{}\n", i),
+            })
+            .collect();
+        let diff = unified_diff(&old, &new, "old", "new");
+        assert!(diff.contains("@@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n"));
+        assert!(diff.contains("@@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n+nineteen\n 20\n"));
+    }
+
+    #[test]
+    fn test_unified_diff_from_empty() {
+        assert_eq!(
+            unified_diff("", "a\n", "old", "new"),
+            "--- old\n+++ new\n@@ -0,0 +1,1 @@\n+a\n"
+        );
+    }
+
+    #[test]
+    fn test_check_or_bless() {
+        let path =
+            std::env::temp_dir().join(format!("toml-scaffold-drift-{}.toml", std::process::id()));
+        std::fs::write(&path, "key = 1\n").unwrap();
+
+        let result = check_or_bless("key = 2\n", &path, false);
+        match result {
+            Err(DriftError::OutOfDate { diff, .. }) => {
+                assert!(diff.contains("-key = 1\n+key = 2\n"))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        check_or_bless("key = 2\n", &path, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "key = 2\n");
+        check_or_bless("key = 2\n", &path, false).unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
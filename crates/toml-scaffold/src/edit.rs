@@ -0,0 +1,306 @@
+use crate::field_path::FieldPath;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike};
+
+/// Error returned by [`set_value`] and [`rerender`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// The path has no segments
+    EmptyPath,
+    /// A parent of the path holds a value that is not a table
+    NotATable(FieldPath),
+    /// A segment indexing an array of tables is not an index of one of its elements
+    NoSuchElement(FieldPath),
+    /// The scaffold has no value at the path
+    NotInScaffold(FieldPath),
+}
+
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::EmptyPath => f.write_str("cannot set a value at an empty path"),
+            EditError::NotATable(path) => write!(f, "`{}` is not a table", path),
+            EditError::NoSuchElement(path) => {
+                write!(f, "`{}` is not an element of an array of tables", path)
+            }
+            EditError::NotInScaffold(path) => write!(f, "the scaffold has no value at `{}`", path),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Parse a generated scaffold into an editable document, doc comments become decor.
+///
+/// Parsing keeps the layout of the TOML scaffold, including its section separators
+pub(crate) fn to_document(scaffold: &str) -> Result<DocumentMut, toml::ser::Error> {
+    use serde::ser::Error;
+
+    scaffold
+        .parse()
+        .map_err(|e| toml::ser::Error::custom(format!("generated invalid TOML: {}", e)))
+}
+
+/// Sets the value at `path` in a document, keeping comments and formatting around it.
+///
+/// Existing values keep their surrounding whitespace and trailing comment. Missing
+/// keys are appended to their table, creating `[section]` tables for missing parents.
+/// Numeric segments index arrays of tables, e.g. `backends.0.url`.
+pub fn set_value(
+    doc: &mut DocumentMut,
+    path: &FieldPath,
+    value: impl Into<toml::Value>,
+) -> Result<(), EditError> {
+    let Some((last, parents)) = path.segments().split_last() else {
+        return Err(EditError::EmptyPath);
+    };
+
+    let table = parent_table(doc, parents, true)?;
+    let mut new_value = to_edit_value(&value.into());
+    match table.get_mut(last) {
+        Some(Item::Value(old)) => {
+            *new_value.decor_mut() = old.decor().clone();
+            *old = new_value;
+        }
+        Some(item) => *item = Item::Value(new_value),
+        None => {
+            table.insert(last, Item::Value(new_value));
+        }
+    }
+    Ok(())
+}
+
+/// Adds the keys and tables of `scaffold` that are missing from `doc`.
+///
+/// New entries bring their doc comments along. Existing values, comments and formatting
+/// are left alone, and arrays of tables are not merged element by element.
+pub fn merge_document(doc: &mut DocumentMut, scaffold: &DocumentMut) {
+    merge_table(doc.as_table_mut(), scaffold.as_table());
+}
+
+/// Replaces the entry at `path` in `doc` with the one rendered in `scaffold`.
+///
+/// The entry is re-rendered with its doc comments and layout from the scaffold, the rest
+/// of the document is kept. Missing parents are created like in [`set_value`].
+pub fn rerender(
+    doc: &mut DocumentMut,
+    scaffold: &DocumentMut,
+    path: &FieldPath,
+) -> Result<(), EditError> {
+    let Some((last, parents)) = path.segments().split_last() else {
+        return Err(EditError::EmptyPath);
+    };
+    let not_in_scaffold = || EditError::NotInScaffold(path.clone());
+
+    let mut scaffold = scaffold.clone();
+    let (key, mut item) =
+        match parent(&mut scaffold, parents, false).map_err(|_| not_in_scaffold())? {
+            Parent::Table(table) => {
+                let (key, item) = table.get_key_value(last).ok_or_else(not_in_scaffold)?;
+                (Some(key.clone()), item.clone())
+            }
+            Parent::Array(array) => {
+                let table = element(array, last).ok_or_else(not_in_scaffold)?;
+                (None, Item::Table(table.clone()))
+            }
+        };
+    clear_positions(&mut item);
+
+    match parent(doc, parents, true)? {
+        Parent::Table(table) => match table.get_key_value_mut(last) {
+            Some((mut old_key, old)) => {
+                let key = key.unwrap();
+                *old_key.leaf_decor_mut() = key.leaf_decor().clone();
+                keep_position(old, &mut item);
+                *old = item;
+            }
+            None => {
+                table.entry_format(&key.unwrap()).or_insert(item);
+            }
+        },
+        Parent::Array(array) => {
+            let old = element(array, last).ok_or_else(|| EditError::NoSuchElement(path.clone()))?;
+            let Item::Table(mut table) = item else {
+                unreachable!("elements of arrays of tables are tables")
+            };
+            table.set_position(old.position());
+            *old = table;
+        }
+    }
+    Ok(())
+}
+
+/// The table or array of tables a path segment is looked up in
+enum Parent<'a> {
+    Table(&'a mut dyn TableLike),
+    Array(&'a mut ArrayOfTables),
+}
+
+/// Walk `segments` from the document root, optionally creating missing tables
+fn parent<'a>(
+    doc: &'a mut DocumentMut,
+    segments: &[String],
+    create: bool,
+) -> Result<Parent<'a>, EditError> {
+    let mut current = Parent::Table(doc.as_table_mut());
+    for (i, segment) in segments.iter().enumerate() {
+        let path = || FieldPath::from_vec(segments[..=i].to_vec());
+        current = match current {
+            Parent::Table(table) => {
+                if create && table.get(segment).is_none() {
+                    table.insert(segment, Item::Table(Table::new()));
+                }
+                match table.get_mut(segment) {
+                    Some(Item::ArrayOfTables(array)) => Parent::Array(array),
+                    Some(item) => Parent::Table(
+                        item.as_table_like_mut()
+                            .ok_or_else(|| EditError::NotATable(path()))?,
+                    ),
+                    None => return Err(EditError::NotATable(path())),
+                }
+            }
+            Parent::Array(array) => Parent::Table(
+                element(array, segment).ok_or_else(|| EditError::NoSuchElement(path()))?,
+            ),
+        };
+    }
+    Ok(current)
+}
+
+/// Walk `segments` to the table holding the last segment of a path
+fn parent_table<'a>(
+    doc: &'a mut DocumentMut,
+    segments: &[String],
+    create: bool,
+) -> Result<&'a mut dyn TableLike, EditError> {
+    match parent(doc, segments, create)? {
+        Parent::Table(table) => Ok(table),
+        Parent::Array(_) => Err(EditError::NotATable(FieldPath::from_vec(segments.to_vec()))),
+    }
+}
+
+/// The element of an array of tables at a numeric segment
+fn element<'a>(array: &'a mut ArrayOfTables, segment: &str) -> Option<&'a mut Table> {
+    array.get_mut(segment.parse().ok()?)
+}
+
+/// Recursively add the entries of `scaffold` missing from `table`
+fn merge_table(table: &mut dyn TableLike, scaffold: &dyn TableLike) {
+    for (key, item) in scaffold.iter() {
+        match table.get_mut(key) {
+            Some(existing) => {
+                if let (Some(existing), Some(item)) =
+                    (existing.as_table_like_mut(), item.as_table_like())
+                {
+                    merge_table(existing, item);
+                }
+            }
+            None => {
+                let mut item = item.clone();
+                clear_positions(&mut item);
+                table
+                    .entry_format(scaffold.key(key).unwrap())
+                    .or_insert(item);
+            }
+        }
+    }
+}
+
+/// Drop the scaffold positions of copied tables so they follow their new neighbours
+fn clear_positions(item: &mut Item) {
+    match item {
+        Item::Table(table) => {
+            table.set_position(None);
+            table.iter_mut().for_each(|(_, item)| clear_positions(item));
+        }
+        Item::ArrayOfTables(array) => {
+            for table in array.iter_mut() {
+                table.set_position(None);
+                table.iter_mut().for_each(|(_, item)| clear_positions(item));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keep a replaced table where it was in the document
+fn keep_position(old: &Item, new: &mut Item) {
+    if let (Item::Table(old), Item::Table(new)) = (old, new) {
+        new.set_position(old.position());
+    }
+}
+
+/// Convert a `toml` value into a `toml_edit` value with default formatting
+fn to_edit_value(value: &toml::Value) -> toml_edit::Value {
+    // Inline TOML produced by `Display` always parses back
+    value.to_string().parse().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(key: &str) -> FieldPath {
+        key.parse().unwrap()
+    }
+
+    #[test]
+    fn test_set_existing_value_keeps_comments() {
+        let mut doc: DocumentMut = "# Port\nport = 80 # default\n".parse().unwrap();
+        set_value(&mut doc, &path("port"), 8080).unwrap();
+        assert_eq!(doc.to_string(), "# Port\nport = 8080 # default\n");
+    }
+
+    #[test]
+    fn test_set_inline_table_value() {
+        let mut doc: DocumentMut = "db = { host = \"a\", port = 1 }\n".parse().unwrap();
+        set_value(&mut doc, &path("db.port"), 2).unwrap();
+        assert_eq!(doc.to_string(), "db = { host = \"a\", port = 2 }\n");
+    }
+
+    #[test]
+    fn test_set_missing_creates_tables() {
+        let mut doc: DocumentMut = "name = \"x\"\n".parse().unwrap();
+        set_value(&mut doc, &path("server.port"), 1).unwrap();
+        assert_eq!(doc.to_string(), "name = \"x\"\n\n[server]\nport = 1\n");
+    }
+
+    #[test]
+    fn test_set_through_scalar() {
+        let mut doc: DocumentMut = "name = \"x\"\n".parse().unwrap();
+        assert_eq!(
+            set_value(&mut doc, &path("name.first"), 1),
+            Err(EditError::NotATable(path("name")))
+        );
+        assert_eq!(
+            set_value(&mut doc, &FieldPath::new(), 1),
+            Err(EditError::EmptyPath)
+        );
+    }
+
+    #[test]
+    fn test_set_array_of_tables_element() {
+        let mut doc: DocumentMut =
+            "[[backends]]\nurl = \"a\" # primary\n\n[[backends]]\nurl = \"b\"\n"
+                .parse()
+                .unwrap();
+        set_value(&mut doc, &path("backends.1.url"), "c").unwrap();
+        set_value(&mut doc, &path("backends.0.url"), "d").unwrap();
+        assert_eq!(
+            doc.to_string(),
+            "[[backends]]\nurl = \"d\" # primary\n\n[[backends]]\nurl = \"c\"\n"
+        );
+
+        assert_eq!(
+            set_value(&mut doc, &path("backends.2.url"), "e"),
+            Err(EditError::NoSuchElement(path("backends.2")))
+        );
+        assert_eq!(
+            set_value(&mut doc, &path("backends.first.url"), "e"),
+            Err(EditError::NoSuchElement(path("backends.first")))
+        );
+        assert_eq!(
+            set_value(&mut doc, &path("backends.0"), "e"),
+            Err(EditError::NotATable(path("backends")))
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::field_path::FieldPath;
+use crate::format::{append_comment, append_section_separator};
+use crate::schema::SchemaInfo;
+use std::collections::HashMap;
+
+/// Naming convention that maps field paths to environment variable names.
+///
+/// With prefix `APP` and separator `__`, the field path `server.port` maps to `APP__SERVER__PORT`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvNaming {
+    /// Prefix prepended to every variable name, empty for none
+    pub prefix: String,
+    /// Separator between the prefix and each path segment
+    pub separator: String,
+}
+
+impl EnvNaming {
+    /// Creates a naming convention with the given prefix and separator.
+    pub fn new(prefix: impl Into<String>, separator: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            separator: separator.into(),
+        }
+    }
+
+    /// Returns the environment variable name for a field path.
+    ///
+    /// Segments are uppercased and characters that are not valid in variable names become `_`.
+    pub fn var_name(&self, path: &FieldPath) -> String {
+        let mut parts = Vec::new();
+        if !self.prefix.is_empty() {
+            parts.push(self.prefix.clone());
+        }
+        for segment in path.segments() {
+            parts.push(
+                segment
+                    .chars()
+                    .map(|c| {
+                        if c.is_ascii_alphanumeric() {
+                            c.to_ascii_uppercase()
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        parts.join(&self.separator)
+    }
+}
+
+impl Default for EnvNaming {
+    /// No prefix and `__` as separator.
+    fn default() -> Self {
+        Self::new("", "__")
+    }
+}
+
+/// Format TOML value as `.env` lines with comments, one variable per leaf field.
+///
+/// Fields are walked in the order of the schema, so fields of a missing table still get one
+/// commented out variable each. Fails when two fields map to the same variable name.
+pub fn format_with_comments(
+    value: &toml::Value,
+    info: &SchemaInfo,
+    naming: &EnvNaming,
+) -> Result<String, String> {
+    let mut result = String::new();
+
+    // Append root description if at root level
+    if info.comments.contains_key(&FieldPath::new()) {
+        append_comment(&mut result, info, &FieldPath::new());
+        result.push('\n');
+    }
+
+    let mut writer = EnvWriter {
+        info,
+        naming,
+        names: HashMap::new(),
+    };
+    let root = FieldPath::new();
+    result.push_str(&writer.format_table(value.as_table(), &root, &root)?);
+    Ok(result)
+}
+
+/// Walks a value along the schema, remembering the field of every variable written
+struct EnvWriter<'a> {
+    info: &'a SchemaInfo,
+    naming: &'a EnvNaming,
+    names: HashMap<String, FieldPath>,
+}
+
+impl EnvWriter<'_> {
+    /// Format the variables of a table, `None` when it is missing from the value.
+    ///
+    /// `schema_path` is `path` without the indices of array elements.
+    fn format_table(
+        &mut self,
+        table: Option<&toml::Table>,
+        path: &FieldPath,
+        schema_path: &FieldPath,
+    ) -> Result<String, String> {
+        let info = self.info;
+        let mut result = String::new();
+
+        // Keys missing from the schema, e.g. of maps, come first as they are in the value
+        let schema_keys: Vec<&String> = info
+            .ordered_fields
+            .iter()
+            .filter(|field| field.len() == schema_path.len() + 1 && field.starts_with(schema_path))
+            .filter(|field| !info.hidden.contains(*field))
+            .map(|field| field.segments().last().unwrap())
+            .collect();
+        let mut keys: Vec<&String> = table
+            .into_iter()
+            .flat_map(|table| table.keys())
+            .filter(|key| !schema_keys.contains(key))
+            .chain(schema_keys.iter().copied())
+            .collect();
+        // Stable sort, keys with the same priority keep their order
+        keys.sort_by_key(|key| {
+            info.orders
+                .get(&schema_path.child((*key).clone()))
+                .copied()
+                .unwrap_or(0)
+        });
+
+        let mut sections = Vec::new();
+        for key in keys {
+            let value = table.and_then(|table| table.get(key));
+            let current_path = path.child(key.clone());
+            let current_schema_path = schema_path.child(key.clone());
+            let is_object = info
+                .types
+                .get(&current_schema_path)
+                .is_some_and(|types| types.iter().any(|t| t == "object"));
+            let has_fields = info.all_fields.iter().any(|field| {
+                field.len() > current_schema_path.len() && field.starts_with(&current_schema_path)
+            });
+            match value {
+                Some(toml::Value::Table(_)) => sections.push((key, value)),
+                None if is_object && has_fields => sections.push((key, value)),
+                Some(toml::Value::Array(items))
+                    if !items.is_empty() && items.iter().all(toml::Value::is_table) =>
+                {
+                    sections.push((key, value))
+                }
+                _ => {
+                    append_comment(&mut result, info, &current_schema_path);
+                    let name = self.var_name(&current_path)?;
+                    match value {
+                        Some(value) => {
+                            result.push_str(&format!("{}={}\n", name, format_value(value)))
+                        }
+                        None => {
+                            // Rule 17: Missing fields are commented out, empty strings read
+                            // better as a bare `VAR=`
+                            let example = info
+                                .examples
+                                .get(&current_schema_path)
+                                .filter(|example| example.as_str() != Some(""))
+                                .map_or(String::new(), format_value);
+                            result.push_str(&format!("# {}={}\n", name, example));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Nested tables are grouped like TOML sections, elements of arrays of tables like
+        // `[[key]]` sections with their index as a segment
+        for (key, value) in sections {
+            let current_path = path.child(key.clone());
+            let current_schema_path = schema_path.child(key.clone());
+            let elements: Vec<(FieldPath, Option<&toml::Table>)> = match value {
+                Some(toml::Value::Array(items)) => items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (current_path.child(i.to_string()), item.as_table()))
+                    .collect(),
+                _ => vec![(current_path, value.and_then(toml::Value::as_table))],
+            };
+            for (element_path, element) in elements {
+                let nested = self.format_table(element, &element_path, &current_schema_path)?;
+                if nested.is_empty() {
+                    continue;
+                }
+                append_section_separator(&mut result);
+                append_comment(&mut result, info, &current_schema_path);
+                result.push_str(&nested);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// The variable name of a field, failing when another field already uses it
+    fn var_name(&mut self, path: &FieldPath) -> Result<String, String> {
+        let name = self.naming.var_name(path);
+        match self.names.get(&name) {
+            Some(other) if other != path => Err(format!(
+                "fields `{}` and `{}` both map to the environment variable `{}`",
+                other, path, name
+            )),
+            _ => {
+                self.names.insert(name.clone(), path.clone());
+                Ok(name)
+            }
+        }
+    }
+}
+
+/// Convert TOML value to a `.env` value, quoting it when needed
+fn format_value(value: &toml::Value) -> String {
+    let raw = match value {
+        toml::Value::String(s) => s.clone(),
+        // Other values use their inline TOML representation
+        _ => value.to_string(),
+    };
+
+    let is_plain = !raw.is_empty()
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.,:/@+".contains(c));
+    if is_plain {
+        raw
+    } else if !raw.contains('\'') && !raw.contains('\n') {
+        // Single quotes keep the value literal
+        format!("'{}'", raw)
+    } else {
+        let escaped = raw
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('$', "\\$")
+            .replace('\n', "\\n");
+        format!("\"{}\"", escaped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_var_name() {
+        let naming = EnvNaming::new("APP", "__");
+        let path = FieldPath::from_vec(vec!["server".to_string(), "max-conns".to_string()]);
+        assert_eq!(naming.var_name(&path), "APP__SERVER__MAX_CONNS");
+    }
+
+    #[test]
+    fn test_var_name_without_prefix() {
+        let naming = EnvNaming::default();
+        let path = FieldPath::from_vec(vec!["log".to_string(), "level".to_string()]);
+        assert_eq!(naming.var_name(&path), "LOG__LEVEL");
+    }
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_value(&toml::Value::String("info".into())), "info");
+        assert_eq!(format_value(&toml::Value::String("".into())), "''");
+        assert_eq!(
+            format_value(&toml::Value::String("hello world".into())),
+            "'hello world'"
+        );
+        assert_eq!(
+            format_value(&toml::Value::String("it's $HOME".into())),
+            "\"it's \\$HOME\""
+        );
+        assert_eq!(format_value(&toml::Value::Integer(8080)), "8080");
+        assert_eq!(format_value(&toml::Value::Boolean(false)), "false");
+        let arr = toml::Value::Array(vec!["a".into(), "b".into()]);
+        assert_eq!(format_value(&arr), "'[\"a\", \"b\"]'");
+    }
+}
@@ -2,7 +2,7 @@
 ///
 /// This struct is used to represent paths to fields in nested structures,
 /// where each segment is a field name.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
 pub struct FieldPath(Vec<String>);
 
 impl FieldPath {
@@ -33,6 +33,11 @@ impl FieldPath {
         self.0.len()
     }
 
+    /// Returns `true` if this path has no segments.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Checks if this path starts with another path.
     pub fn starts_with(&self, other: &FieldPath) -> bool {
         self.0.starts_with(&other.0)
@@ -43,21 +48,109 @@ impl FieldPath {
         self.0.get(index)
     }
 
+    /// Returns the segments of this path.
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
     /// Converts this path to a TOML dotted key string.
     pub fn as_dotted_key(&self) -> String {
-        use toml_writer::TomlWrite;
+        write_key(&self.0)
+    }
+}
+
+/// Writes a TOML key, joining segments with dots and quoting those that are not bare keys.
+///
+/// Every key in generated TOML goes through this function (Rule 22).
+pub(crate) fn write_key(segments: &[String]) -> String {
+    use toml_writer::TomlWrite;
+
+    let mut result = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            result.push('.');
+        }
+        let _ = result.key(segment.as_str());
+    }
+    result
+}
+
+impl std::fmt::Display for FieldPath {
+    /// Formats the path as a TOML dotted key.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.as_dotted_key())
+    }
+}
+
+impl std::str::FromStr for FieldPath {
+    type Err = toml::de::Error;
+
+    /// Parses a TOML dotted key such as `server.port` or `headers."X-Title"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use serde::de::Error;
+
+        if !is_dotted_key(s) {
+            return Err(toml::de::Error::custom(format!(
+                "invalid dotted key `{}`",
+                s
+            )));
+        }
 
-        let mut result = String::new();
-        for (i, segment) in self.0.iter().enumerate() {
-            if i > 0 {
-                result.push('.');
+        // Let the TOML parser handle quoting and escaping of key segments
+        let table: toml::Table = toml::from_str(&format!("{} = 0", s))?;
+        let mut segments = Vec::new();
+        let mut current = &table;
+        loop {
+            let mut entries = current.iter();
+            match (entries.next(), entries.next()) {
+                (Some((key, value)), None) => {
+                    segments.push(key.clone());
+                    match value {
+                        toml::Value::Table(nested) => current = nested,
+                        _ => return Ok(Self(segments)),
+                    }
+                }
+                _ => {
+                    return Err(toml::de::Error::custom(format!(
+                        "invalid dotted key `{}`",
+                        s
+                    )))
+                }
             }
-            let _ = result.key(segment.as_str());
         }
-        result
     }
 }
 
+/// Whether `s` only holds key characters, dots and whitespace outside of quoted segments,
+/// so that nothing after the key, such as `= 1` or a comment, is parsed with it
+fn is_dotted_key(s: &str) -> bool {
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => {
+                        chars.next();
+                    }
+                    Some('\n') | None => return false,
+                    Some(_) => {}
+                }
+            },
+            '\'' => loop {
+                match chars.next() {
+                    Some('\'') => break,
+                    Some('\n') | None => return false,
+                    Some(_) => {}
+                }
+            },
+            c if c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ' | '\t') => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,6 +160,7 @@ mod tests {
     fn test_new() {
         let path = FieldPath::new();
         assert_eq!(path.len(), 0);
+        assert!(path.is_empty());
         assert_eq!(path.as_dotted_key(), "");
     }
 
@@ -115,6 +209,13 @@ mod tests {
         assert_eq!(path.get(3), None);
     }
 
+    #[test]
+    fn test_segments() {
+        let path = FieldPath::from_vec(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(path.segments(), ["a".to_string(), "b".to_string()]);
+        assert!(FieldPath::new().segments().is_empty());
+    }
+
     #[test]
     fn test_field_with_dots() {
         let path = FieldPath::from_vec(vec!["field.with.dots".to_string()]);
@@ -122,6 +223,40 @@ mod tests {
         assert_eq!(path.as_dotted_key(), "\"field.with.dots\"");
     }
 
+    #[test]
+    fn test_from_str() {
+        let path: FieldPath = "server.port".parse().unwrap();
+        assert_eq!(
+            path,
+            FieldPath::from_vec(vec!["server".to_string(), "port".to_string()])
+        );
+
+        let path: FieldPath = "headers.\"X-Title.v2\"".parse().unwrap();
+        assert_eq!(path.get(1), Some(&"X-Title.v2".to_string()));
+        assert_eq!(path.to_string(), "headers.\"X-Title.v2\"");
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert!("".parse::<FieldPath>().is_err());
+        assert!("a = 1\nb".parse::<FieldPath>().is_err());
+        assert!("a..b".parse::<FieldPath>().is_err());
+        assert!("a = 1 #".parse::<FieldPath>().is_err());
+        assert!("a #".parse::<FieldPath>().is_err());
+        assert!("a = 1".parse::<FieldPath>().is_err());
+        assert!("[a]\nb".parse::<FieldPath>().is_err());
+        assert!("a\nb".parse::<FieldPath>().is_err());
+        assert!("\"a".parse::<FieldPath>().is_err());
+    }
+
+    #[test]
+    fn test_from_str_quoted_specials() {
+        let path: FieldPath = "a.\"b = #[c]\".'d\"e'".parse().unwrap();
+        assert_eq!(path.segments(), ["a", "b = #[c]", "d\"e"]);
+        let path: FieldPath = " a . b ".parse().unwrap();
+        assert_eq!(path.segments(), ["a", "b"]);
+    }
+
     #[test]
     fn test_hash_map_key() {
         let mut map = HashMap::new();
@@ -1,208 +1,109 @@
-use crate::field_path::FieldPath;
-use std::collections::{HashMap, HashSet};
+use crate::field_path::{write_key, FieldPath};
+use crate::options::FieldOrder;
+use crate::schema::SchemaInfo;
+use std::collections::HashMap;
 use toml_writer::TomlWrite;
 
 /// Format TOML value with comments at the appropriate paths
-pub fn format_with_comments(
-    value: &toml::Value,
-    comments: &HashMap<FieldPath, String>,
-    all_fields: &HashSet<FieldPath>,
-    optional_fields: &HashSet<FieldPath>,
-    formats: &HashMap<FieldPath, String>,
-    path: &FieldPath,
-) -> String {
+pub fn format_with_comments(value: &toml::Value, info: &SchemaInfo, path: &FieldPath) -> String {
+    let comments = &info.comments;
+    let formats = &info.formats;
+
     match value {
         toml::Value::Table(table) => {
             let mut result = String::new();
 
             // Append root description if at root level
-            if path.len() == 0 {
-                append_comment(&mut result, comments, &FieldPath::new());
+            if path.is_empty() {
+                append_comment(&mut result, info, &FieldPath::new());
                 if comments.contains_key(&FieldPath::new()) {
                     result.push('\n');
                 }
             }
 
-            let (inline_keys, nested_tables, array_tables) = categorize_table_keys(table);
-
-            // Process scalar fields first
-            for key in inline_keys {
-                let val = &table[key];
-                let current_path = path.child(key.clone());
-                append_comment(&mut result, comments, &current_path);
-                // Rule 11: Use spaces around = for assignments
-                let _ = result.key(key.as_str());
-                result.push_str(&format!(
-                    " = {}\n",
-                    format_value(val, comments, formats, &current_path)
-                ));
-            }
-
-            // Rule 17: Show missing optional fields as comments
-            for field in all_fields {
-                if field.len() != path.len() + 1 {
-                    continue; // Not a direct child
-                }
-                if !field.starts_with(path) {
-                    continue; // Not under current path
-                }
-                let key = field.get(path.len()).unwrap();
-
-                if optional_fields.contains(field) && !table.contains_key(key) {
-                    append_comment(&mut result, comments, field);
-                    result.push_str("# ");
-                    let _ = result.key(key.as_str());
-                    result.push_str(" = ...\n");
-                }
-            }
-
-            // Process nested tables
-            for key in nested_tables {
-                let val = &table[key];
-                let current_path = path.child(key.clone());
-                let format_pref = formats.get(&current_path).map(|s| s.as_str());
-
-                match format_pref {
-                    Some("inline") => {
-                        // Inline format: key = { ... }
-                        append_comment(&mut result, comments, &current_path);
-                        let _ = result.key(key.as_str());
-                        if let toml::Value::Table(nested) = val {
-                            let items: Vec<String> = nested
-                                .iter()
-                                .map(|(k, v)| {
-                                    let subpath = current_path.child(k.clone());
-                                    format!(
-                                        "{} = {}",
-                                        k,
-                                        format_value(v, comments, formats, &subpath)
-                                    )
-                                })
-                                .collect();
-                            result.push_str(&format!(" = {{ {} }}\n", items.join(", ")));
+            let aligned = is_aligned(info, path);
+            let (inline_keys, nested_tables, array_tables) =
+                categorize_table_keys(table, info, path);
+
+            // Process scalar fields first, with inline and dotted tables in place
+            let lines: Vec<KeyLine> = inline_keys
+                .into_iter()
+                .flat_map(|key| {
+                    let current_path = path.child(key.clone());
+                    match &table[key] {
+                        toml::Value::Table(nested) => {
+                            // Tables ordered among scalars default to dotted keys
+                            let default = if nested.is_empty() {
+                                "inline"
+                            } else {
+                                "dotted-nested"
+                            };
+                            let format = format_of(formats, &current_path)
+                                .filter(|f| is_in_place(f))
+                                .unwrap_or(default);
+                            nested_key_lines(nested, &current_path, format, info)
                         }
-                    }
-                    Some("dotted") => {
-                        // Dotted format: key.subkey = value (one level only)
-                        append_comment(&mut result, comments, &current_path);
-                        if let toml::Value::Table(nested) = val {
-                            for (subkey, subval) in nested {
-                                let subpath = current_path.child(subkey.clone());
-                                append_comment(&mut result, comments, &subpath);
-                                result.push_str(&format!(
-                                    "{}.{} = {}\n",
-                                    key,
-                                    subkey,
-                                    format_value(subval, comments, formats, &subpath)
-                                ));
-                            }
-                        }
-                    }
-                    Some("dotted-nested") => {
-                        // Dotted nested format: recursively flatten all levels
-                        append_comment(&mut result, comments, &current_path);
-                        if let toml::Value::Table(nested) = val {
-                            flatten_dotted(
-                                &mut result,
-                                key,
-                                nested,
-                                comments,
-                                formats,
-                                &current_path,
-                            );
-                        }
-                    }
-                    Some(fmt) if fmt.starts_with("*") => {
-                        // Child format: [section] with custom format for children
-                        let child_format = &fmt[1..]; // Remove * prefix
-                        append_section_separator(&mut result);
-                        append_comment(&mut result, comments, &current_path);
-                        result.push_str(&format!("[{}]\n", current_path.as_dotted_key()));
-
-                        if let toml::Value::Table(nested) = val {
-                            match child_format {
-                                "dotted" => {
-                                    // Children use dotted format
-                                    for (subkey, subval) in nested {
-                                        let subpath = current_path.child(subkey.clone());
-                                        if let toml::Value::Table(sub_nested) = subval {
-                                            for (sub_subkey, sub_subval) in sub_nested {
-                                                let sub_subpath = subpath.child(sub_subkey.clone());
-                                                append_comment(&mut result, comments, &sub_subpath);
-                                                result.push_str(&format!(
-                                                    "{}.{} = {}\n",
-                                                    subkey,
-                                                    sub_subkey,
-                                                    format_value(
-                                                        sub_subval,
-                                                        comments,
-                                                        formats,
-                                                        &sub_subpath
-                                                    )
-                                                ));
-                                            }
-                                        } else {
-                                            append_comment(&mut result, comments, &subpath);
-                                            result.push_str(&format!(
-                                                "{} = {}\n",
-                                                subkey,
-                                                format_value(subval, comments, formats, &subpath)
-                                            ));
-                                        }
-                                    }
-                                }
-                                "dotted-nested" => {
-                                    // Children use dotted-nested format
-                                    for (subkey, subval) in nested {
-                                        let subpath = current_path.child(subkey.clone());
-                                        if let toml::Value::Table(sub_nested) = subval {
-                                            flatten_dotted(
-                                                &mut result,
-                                                subkey,
-                                                sub_nested,
-                                                comments,
-                                                formats,
-                                                &subpath,
-                                            );
-                                        } else {
-                                            append_comment(&mut result, comments, &subpath);
-                                            result.push_str(&format!(
-                                                "{} = {}\n",
-                                                subkey,
-                                                format_value(subval, comments, formats, &subpath)
-                                            ));
-                                        }
+                        val => {
+                            let value = match column_rows(val, info, &current_path) {
+                                Some(rows) if aligned => format_columns(&rows, info, &current_path),
+                                _ => match val {
+                                    toml::Value::Array(arr)
+                                        if matches!(arr.first(), Some(toml::Value::Table(_))) =>
+                                    {
+                                        format_inline_tables(arr, info, &current_path)
                                     }
-                                }
-                                _ => {
-                                    // Default standard format for children
-                                    result.push_str(&format_with_comments(
-                                        val,
-                                        comments,
-                                        all_fields,
-                                        optional_fields,
-                                        formats,
-                                        &current_path,
-                                    ));
-                                }
-                            }
+                                    _ => format_value(val, comments, formats, &current_path),
+                                },
+                            };
+                            vec![KeyLine::new(current_path, path.len(), value)]
                         }
                     }
-                    _ => {
-                        // Standard format: [section]
-                        append_section_separator(&mut result);
-                        append_comment(&mut result, comments, &current_path);
-                        result.push_str(&format!("[{}]\n", current_path.as_dotted_key()));
-                        result.push_str(&format_with_comments(
-                            val,
-                            comments,
-                            all_fields,
-                            optional_fields,
-                            formats,
-                            &current_path,
-                        ));
+                })
+                .collect();
+
+            // Rule 21: Pad keys so `=` signs line up within the table
+            let key_width = if aligned {
+                lines
+                    .iter()
+                    .map(|line| line.marker(info).len() + line.key.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            } else {
+                0
+            };
+
+            // Rule 17: Show missing fields as comments
+            // after the keys, or where they are defined with `FieldOrder::Definition`
+            let position = |field: &FieldPath| {
+                let field = FieldPath::from_vec(field.segments()[..=path.len()].to_vec());
+                info.ordered_fields.iter().position(|f| *f == field)
+            };
+            let mut missing = missing_fields(info, table, path).into_iter().peekable();
+            let mut all_lines = Vec::new();
+            for line in lines {
+                if info.field_order == FieldOrder::Definition {
+                    let index = position(&line.path);
+                    while let Some(field) = missing.next_if(|field| position(field) < index) {
+                        all_lines.push(KeyLine::placeholder(field.clone(), path.len(), info));
                     }
                 }
+                all_lines.push(line);
+            }
+            all_lines
+                .extend(missing.map(|field| KeyLine::placeholder(field.clone(), path.len(), info)));
+            let lines = all_lines;
+            append_key_lines(&mut result, info, lines, key_width, path.len() + 1);
+
+            // Process nested tables, a `*format` applies to the children of the section
+            for key in nested_tables {
+                let current_path = path.child(key.clone());
+                let start = result.len();
+                // Rule 6: Standard format: [section]
+                append_section_separator(&mut result);
+                append_comment(&mut result, info, &current_path);
+                result.push_str(&format!("[{}]\n", current_path.as_dotted_key()));
+                result.push_str(&format_with_comments(&table[key], info, &current_path));
+                comment_out_from(&mut result, start, info, &current_path);
             }
 
             // Rule 5: Process array of tables using [[item]] syntax
@@ -210,69 +111,526 @@ pub fn format_with_comments(
                 let val = &table[key];
                 let current_path = path.child(key.clone());
 
+                let start = result.len();
                 if let toml::Value::Array(arr) = val {
                     for item in arr {
                         append_section_separator(&mut result);
-                        append_comment(&mut result, comments, &current_path);
+                        append_comment(&mut result, info, &current_path);
                         result.push_str(&format!("[[{}]]\n", current_path.as_dotted_key()));
-                        result.push_str(&format_with_comments(
-                            item,
-                            comments,
-                            all_fields,
-                            optional_fields,
-                            formats,
-                            &current_path,
-                        ));
+                        result.push_str(&format_with_comments(item, info, &current_path));
                     }
                 }
+                comment_out_from(&mut result, start, info, &current_path);
             }
 
             result
         }
+        // Non-table roots are rejected or nested under a key before formatting
         _ => unreachable!(),
     }
 }
 
-/// Rule 15: Categorize table keys into inline, nested tables, and array tables
-fn categorize_table_keys(
+/// A `key = value` line and the field path of its doc comment
+struct KeyLine {
+    path: FieldPath,
+    key: String,
+    value: String,
+    /// Dotted table whose doc comment goes above this line, set on the first line of the table
+    heading: Option<FieldPath>,
+    /// Rule 17: Commented out placeholder of a missing field
+    placeholder: bool,
+}
+
+impl KeyLine {
+    /// A line for the field at `path` in the table `depth` segments deep, keyed by the
+    /// rest of the path, dotted when it has several segments
+    fn new(path: FieldPath, depth: usize, value: String) -> Self {
+        KeyLine {
+            key: write_key(&path.segments()[depth..]),
+            path,
+            value,
+            heading: None,
+            placeholder: false,
+        }
+    }
+
+    /// A commented out line showing an example value of a missing field
+    fn placeholder(path: FieldPath, depth: usize, info: &SchemaInfo) -> Self {
+        let value = match info.examples.get(&path) {
+            // Tables are always inlined so the placeholder stays a single key
+            Some(example @ toml::Value::Table(_)) => example.to_string(),
+            Some(example) => format_value(example, &info.comments, &info.formats, &path),
+            None => "...".to_string(),
+        };
+        KeyLine {
+            placeholder: true,
+            ..Self::new(path, depth, value)
+        }
+    }
+
+    /// The `# ` that comments out placeholders and lines of commented fields
+    fn marker(&self, info: &SchemaInfo) -> &'static str {
+        if self.placeholder || is_commented(info, &self.path) {
+            "# "
+        } else {
+            ""
+        }
+    }
+}
+
+/// Formats rendering a table within its parent rather than as a `[section]`
+fn is_in_place(format: &str) -> bool {
+    matches!(format, "inline" | "dotted" | "dotted-nested")
+}
+
+/// The format of a field, its own or the `*format` of its parent section
+fn format_of<'a>(formats: &'a HashMap<FieldPath, String>, path: &FieldPath) -> Option<&'a str> {
+    if let Some(format) = formats.get(path) {
+        return Some(format.as_str());
+    }
+    let parent = FieldPath::from_vec(path.segments().split_last()?.1.to_vec());
+    formats.get(&parent)?.strip_prefix('*')
+}
+
+/// Key lines of a table rendered within its parent with the `inline`, `dotted` or
+/// `dotted-nested` format
+fn nested_key_lines(
+    nested: &toml::map::Map<String, toml::Value>,
+    current_path: &FieldPath,
+    format: &str,
+    info: &SchemaInfo,
+) -> Vec<KeyLine> {
+    let (comments, formats) = (&info.comments, &info.formats);
+    // Keys are relative to the parent table
+    let depth = current_path.len() - 1;
+    let mut lines = Vec::new();
+    match format {
+        // Rule 18: Empty tables have no keys to dot, keep them as `{}`
+        _ if nested.is_empty() => {
+            return vec![KeyLine::new(current_path.clone(), depth, "{}".to_string())];
+        }
+        "inline" => {
+            // Inline format: key = { ... }
+            let items: Vec<String> = nested
+                .iter()
+                .map(|(k, v)| {
+                    let subpath = current_path.child(k.clone());
+                    let value = format_value(v, comments, formats, &subpath);
+                    format!("{} = {}", write_key(std::slice::from_ref(k)), value)
+                })
+                .collect();
+            let value = format!("{{ {} }}", items.join(", "));
+            return vec![KeyLine::new(current_path.clone(), depth, value)];
+        }
+        "dotted" => {
+            // Dotted format: key.subkey = value (one level only, deeper tables are inline)
+            for (subkey, subval) in nested {
+                let subpath = current_path.child(subkey.clone());
+                let value = format_value(subval, comments, formats, &subpath);
+                lines.push(KeyLine::new(subpath, depth, value));
+            }
+            append_dotted_placeholders(&mut lines, nested, info, current_path, depth);
+        }
+        _ => {
+            // Dotted nested format: recursively flatten all levels
+            flatten_dotted(&mut lines, nested, info, current_path, depth);
+        }
+    }
+    if let Some(first) = lines.first_mut() {
+        first.heading = Some(current_path.clone());
+    }
+    lines
+}
+
+/// Rule 17: Placeholders for fields missing from a table rendered with dotted keys
+fn append_dotted_placeholders(
+    lines: &mut Vec<KeyLine>,
     table: &toml::map::Map<String, toml::Value>,
-) -> (Vec<&String>, Vec<&String>, Vec<&String>) {
+    info: &SchemaInfo,
+    path: &FieldPath,
+    depth: usize,
+) {
+    for field in missing_fields(info, table, path) {
+        lines.push(KeyLine::placeholder(field.clone(), depth, info));
+    }
+}
+
+/// Append the lines of one table with their comments, padding keys other than
+/// placeholders to `key_width`.
+///
+/// Lines are grouped by the field `depth` segments deep in their path, the table's child.
+fn append_key_lines(
+    result: &mut String,
+    info: &SchemaInfo,
+    mut lines: Vec<KeyLine>,
+    key_width: usize,
+    depth: usize,
+) {
+    // Rule 13: Gather the lines of each group where the group first appears. Definition
+    // order is kept as is, only adjacent fields of a group share its header
+    let group_of = |line: &KeyLine| {
+        let field = FieldPath::from_vec(line.path.segments()[..depth].to_vec());
+        info.groups.get(&field)
+    };
+    if info.field_order != FieldOrder::Definition {
+        let mut groups = Vec::new();
+        for line in &lines {
+            let group = group_of(line);
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        lines.sort_by_key(|line| groups.iter().position(|group| *group == group_of(line)));
+    }
+
+    // Rule 11: Use spaces around = for assignments
+    let lines: Vec<(KeyLine, String)> = lines
+        .into_iter()
+        .map(|line| {
+            // The marker of commented fields counts towards the width
+            let width = match line.placeholder {
+                true => 0,
+                false => key_width.saturating_sub(line.marker(info).len()),
+            };
+            let text = format!("{:<width$} = {}", line.key, line.value);
+            (line, text)
+        })
+        .collect();
+    // Trailing comments of a table start at the same column, commented lines included
+    let width = lines
+        .iter()
+        .filter(|(line, text)| trailing_comment(info, &line.path, text).is_some())
+        .map(|(line, text)| line.marker(info).len() + text.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut current = None;
+    for (line, text) in lines {
+        // Separate groups with an empty line and start named groups with a header
+        let group = group_of(&line);
+        if current != Some(group) {
+            if current.is_some() && !result.ends_with("\n\n") {
+                result.push('\n');
+            }
+            if let Some(name) = group.filter(|name| !name.is_empty()) {
+                result.push_str(&format!("# --- {} ---\n", name));
+            }
+            current = Some(group);
+        }
+        let start = result.len();
+        if let Some(heading) = &line.heading {
+            append_comment(result, info, heading);
+        }
+        // Multiline values are commented out line by line
+        let marker = line.marker(info);
+        let text = match trailing_comment(info, &line.path, &text) {
+            Some(comment) => format!("{:<pad$}  # {}", text, comment, pad = width - marker.len()),
+            None => {
+                append_comment(result, info, &line.path);
+                text
+            }
+        };
+        for text in text.lines() {
+            result.push_str(&format!("{}{}\n", marker, text));
+        }
+        comment_out_from(result, start, info, &line.path);
+    }
+}
+
+/// Whether keys of the table at `path` are aligned, set on the table or a parent
+fn is_aligned(info: &SchemaInfo, path: &FieldPath) -> bool {
+    info.aligned.iter().any(|p| path.starts_with(p))
+}
+
+/// The rows of an array of short inline tables sharing the same keys, which can be
+/// rendered as columns. Tables with documented keys keep their `[[key]]` sections
+fn column_rows<'a>(
+    value: &'a toml::Value,
+    info: &SchemaInfo,
+    path: &FieldPath,
+) -> Option<Vec<&'a toml::Table>> {
+    let rows = value
+        .as_array()?
+        .iter()
+        .map(|item| item.as_table())
+        .collect::<Option<Vec<_>>>()?;
+    let first = rows.first().filter(|first| !first.is_empty())?;
+    let short = |row: &toml::Table| {
+        row.len() < 5 && row.values().all(is_scalar) && row.keys().eq(first.keys())
+    };
+    let documented = first
+        .keys()
+        .any(|key| info.comments.contains_key(&path.child(key.clone())));
+    (rows.iter().all(|row| short(row)) && !documented).then_some(rows)
+}
+
+/// Render an array of tables in place, one inline table per line
+fn format_inline_tables(arr: &[toml::Value], info: &SchemaInfo, path: &FieldPath) -> String {
+    let items: Vec<String> = arr
+        .iter()
+        .map(|item| format_single_line(item, info, path))
+        .collect();
+    format!("[\n  {},\n]", items.join(",\n  "))
+}
+
+/// Render a value on a single line, as required within inline tables. Formats of nested
+/// fields apply where they fit on one line: `dotted` and `dotted-nested` tables become
+/// dotted keys of the inline table, arrays stay inline
+fn format_single_line(value: &toml::Value, info: &SchemaInfo, path: &FieldPath) -> String {
+    match value {
+        toml::Value::Table(table) if table.is_empty() => "{}".to_string(),
+        toml::Value::Table(table) => {
+            let mut items = Vec::new();
+            inline_table_entries(&mut items, table, info, path, path.len(), false);
+            format!("{{ {} }}", items.join(", "))
+        }
+        toml::Value::Array(arr) => {
+            let items: Vec<String> = arr
+                .iter()
+                .map(|item| format_single_line(item, info, path))
+                .collect();
+            format!("[{}]", items.join(", "))
+        }
+        _ => format_value(value, &info.comments, &info.formats, path),
+    }
+}
+
+/// Entries of an inline table keyed relative to the table `depth` segments deep, nested
+/// tables are flattened into dotted keys by their format or a `dotted-nested` parent
+fn inline_table_entries(
+    items: &mut Vec<String>,
+    table: &toml::Table,
+    info: &SchemaInfo,
+    path: &FieldPath,
+    depth: usize,
+    flatten: bool,
+) {
+    for (key, value) in table {
+        let subpath = path.child(key.clone());
+        let format = format_of(&info.formats, &subpath);
+        match value {
+            toml::Value::Table(nested)
+                if !nested.is_empty()
+                    && (flatten || matches!(format, Some("dotted" | "dotted-nested"))) =>
+            {
+                let flatten = flatten || format == Some("dotted-nested");
+                inline_table_entries(items, nested, info, &subpath, depth, flatten);
+            }
+            _ => {
+                let value = format_single_line(value, info, &subpath);
+                items.push(format!(
+                    "{} = {}",
+                    write_key(&subpath.segments()[depth..]),
+                    value
+                ));
+            }
+        }
+    }
+}
+
+/// Render inline tables one per line with their entries aligned in columns
+fn format_columns(rows: &[&toml::Table], info: &SchemaInfo, path: &FieldPath) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|(key, value)| {
+                    let subpath = path.child(key.clone());
+                    let value = format_value(value, &info.comments, &info.formats, &subpath);
+                    format!("{} = {}", write_key(std::slice::from_ref(key)), value)
+                })
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = (0..cells[0].len())
+        .map(|column| {
+            cells
+                .iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let lines: Vec<String> = cells
+        .iter()
+        .map(|row| {
+            let padded: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, width))| {
+                    if column + 1 < row.len() {
+                        // The comma stays attached to its cell
+                        format!("{:<width$}", format!("{},", cell), width = width + 1)
+                    } else {
+                        format!("{:<width$}", cell)
+                    }
+                })
+                .collect();
+            format!("{{ {} }}", padded.join(" "))
+        })
+        .collect();
+    format!("[\n  {},\n]", lines.join(",\n  "))
+}
+
+/// Rule 10: The doc comment to place after the value of a key, when trailing comments are
+/// enabled for the key or a parent and both the comment and the line are single lines
+fn trailing_comment(info: &SchemaInfo, path: &FieldPath, line: &str) -> Option<String> {
+    if line.contains('\n') || !info.trailing_comments.iter().any(|p| path.starts_with(p)) {
+        return None;
+    }
+    let comment = info.comment(path)?.trim().to_string();
+    (!comment.is_empty() && !comment.contains('\n')).then_some(comment)
+}
+
+/// Whether the field at `path` or a parent is marked commented
+fn is_commented(info: &SchemaInfo, path: &FieldPath) -> bool {
+    info.commented.iter().any(|p| path.starts_with(p))
+}
+
+/// Comment out everything rendered since `start` when the field or a parent is marked commented.
+///
+/// Doc comment and blank lines are kept as they are.
+fn comment_out_from(result: &mut String, start: usize, info: &SchemaInfo, path: &FieldPath) {
+    if !is_commented(info, path) {
+        return;
+    }
+    let rendered = result.split_off(start);
+    for line in rendered.lines() {
+        if !line.is_empty() && !line.starts_with('#') {
+            result.push_str("# ");
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+}
+
+/// Whether a table holds arrays of tables, directly or in nested tables
+fn has_array_tables(value: &toml::Value) -> bool {
+    value.as_table().is_some_and(|table| {
+        table.values().any(|value| match value {
+            toml::Value::Array(arr) => matches!(arr.first(), Some(toml::Value::Table(_))),
+            _ => has_array_tables(value),
+        })
+    })
+}
+
+/// Rule 15: Categorize table keys into inline keys, nested tables, and array tables.
+///
+/// Keys are ordered by `#[scaffold(order = N)]`, then by the field order of `info`. With
+/// `FieldOrder::Definition`, tables followed by inline keys are returned as inline keys too,
+/// since a `[section]` cannot be followed by keys of its parent. Arrays of tables, and tables
+/// holding them, keep their sections and the inline keys after them move up, as inline
+/// tables would lose the docs of their elements.
+fn categorize_table_keys<'a>(
+    table: &'a toml::map::Map<String, toml::Value>,
+    info: &SchemaInfo,
+    path: &FieldPath,
+) -> (Vec<&'a String>, Vec<&'a String>, Vec<&'a String>) {
+    let mut keys: Vec<&String> = table.keys().collect();
+    if info.field_order == FieldOrder::Alphabetical {
+        keys.sort();
+    }
+    // Stable sort, keys with the same priority keep their order
+    keys.sort_by_key(|key| {
+        info.orders
+            .get(&path.child((*key).clone()))
+            .copied()
+            .unwrap_or(0)
+    });
+
+    // Inline arrays and column layouts are rendered in place instead of as [[key]]
+    let is_array_table = |key: &String| match &table[key] {
+        toml::Value::Array(arr) => {
+            let current_path = path.child(key.clone());
+            matches!(arr.first(), Some(toml::Value::Table(_)))
+                && format_of(&info.formats, &current_path) != Some("inline")
+                && !(is_aligned(info, path)
+                    && column_rows(&table[key], info, &current_path).is_some())
+        }
+        _ => false,
+    };
+    // Inline and dotted tables are rendered in place among the scalar keys
+    let is_section = |key: &String| match &table[key] {
+        toml::Value::Table(_) => {
+            !format_of(&info.formats, &path.child(key.clone())).is_some_and(is_in_place)
+        }
+        _ => is_array_table(key),
+    };
+    // Only the trailing run of sections keeps them in definition order
+    let sections_from = match info.field_order {
+        FieldOrder::Definition => {
+            keys.len() - keys.iter().rev().take_while(|k| is_section(k)).count()
+        }
+        _ => 0,
+    };
+
     let mut inline_keys = Vec::new();
     let mut nested_tables = Vec::new();
     let mut array_tables = Vec::new();
 
-    for (key, val) in table {
-        match val {
-            toml::Value::Table(_) => nested_tables.push(key),
-            toml::Value::Array(arr)
-                if !arr.is_empty() && matches!(arr[0], toml::Value::Table(_)) =>
-            {
-                array_tables.push(key);
-            }
-            _ => inline_keys.push(key),
+    for (i, key) in keys.into_iter().enumerate() {
+        if i < sections_from && !is_array_table(key) && !has_array_tables(&table[key]) {
+            inline_keys.push(key);
+        } else if table[key].is_table() && is_section(key) {
+            nested_tables.push(key);
+        } else if is_array_table(key) {
+            array_tables.push(key);
+        } else {
+            inline_keys.push(key);
         }
     }
 
     (inline_keys, nested_tables, array_tables)
 }
 
+/// Rule 17: Collect direct children of `path` in the schema that are missing from `table`,
+/// in definition order
+pub(crate) fn missing_fields<'a>(
+    info: &'a SchemaInfo,
+    table: &toml::map::Map<String, toml::Value>,
+    path: &FieldPath,
+) -> Vec<&'a FieldPath> {
+    info.ordered_fields
+        .iter()
+        .filter(|field| field.len() == path.len() + 1 && field.starts_with(path))
+        .filter(|field| !info.hidden.contains(*field))
+        .filter(|field| !table.contains_key(field.get(path.len()).unwrap()))
+        .collect()
+}
+
 /// Rule 7 & 9: Append comment lines above a key/section
-fn append_comment(result: &mut String, comments: &HashMap<FieldPath, String>, path: &FieldPath) {
-    if let Some(comment) = comments.get(path) {
-        // Collapse multiple consecutive newlines into single newlines
-        let normalized = comment.replace("\n\n", "\n");
-        for line in normalized.lines() {
-            if line.is_empty() {
-                result.push_str("#\n");
-            } else {
-                result.push_str(&format!("# {}\n", line));
-            }
+pub(crate) fn append_comment(result: &mut String, info: &SchemaInfo, path: &FieldPath) {
+    append_prefixed_comment(result, info, path, "", "#");
+}
+
+/// Append comment lines using the given indentation and comment marker
+pub(crate) fn append_prefixed_comment(
+    result: &mut String,
+    info: &SchemaInfo,
+    path: &FieldPath,
+    indent: &str,
+    marker: &str,
+) {
+    if let Some(comment) = info.comment(path) {
+        append_comment_text(result, &comment, indent, marker);
+    }
+}
+
+/// Append the lines of a comment using the given indentation and comment marker
+pub(crate) fn append_comment_text(result: &mut String, comment: &str, indent: &str, marker: &str) {
+    // Collapse multiple consecutive newlines into single newlines
+    let normalized = comment.replace("\n\n", "\n");
+    for line in normalized.lines() {
+        if line.is_empty() {
+            result.push_str(&format!("{}{}\n", indent, marker));
+        } else {
+            result.push_str(&format!("{}{} {}\n", indent, marker, line));
         }
     }
 }
 
 /// Rule 8: Add empty line before section (if not first)
-fn append_section_separator(result: &mut String) {
+pub(crate) fn append_section_separator(result: &mut String) {
     if !result.is_empty() && !result.ends_with("\n\n") {
         result.push('\n');
     }
@@ -307,7 +665,7 @@ fn format_value(
             result
         }
         toml::Value::Array(arr) => {
-            let format_pref = formats.get(path).map(|s| s.as_str());
+            let format_pref = format_of(formats, path);
 
             // Check if multiline format is requested
             if format_pref == Some("multiline") {
@@ -319,37 +677,43 @@ fn format_value(
             }
 
             // Rule 4: Inline arrays for scalar types
-            if arr.iter().all(|v| is_scalar(v)) {
+            if arr.iter().all(is_scalar) {
                 let items: Vec<String> = arr
                     .iter()
                     .map(|v| format_value(v, comments, formats, path))
                     .collect();
                 format!("[{}]", items.join(", "))
             } else {
-                // Non-scalar arrays handled by format_with_comments
+                // Non-scalar arrays handled by format_with_comments, tables in
+                // arrays rendered in place are always inline
                 format!(
                     "[{}]",
                     arr.iter()
-                        .map(|v| format_value(v, comments, formats, path))
+                        .map(|v| match v {
+                            toml::Value::Table(_) => v.to_string(),
+                            _ => format_value(v, comments, formats, path),
+                        })
                         .collect::<Vec<_>>()
                         .join(", ")
                 )
             }
         }
+        toml::Value::Table(table) if table.is_empty() => "{}".to_string(),
         toml::Value::Table(table) => {
             // Rule 1: Inline maps for small tables with only scalar values and no comments
-            if table.len() < 5
-                && table.values().all(|v| is_scalar(v))
-                && !has_comments(table, comments)
-            {
+            if table.len() < 5 && table.values().all(is_scalar) && !has_comments(table, comments) {
                 let items: Vec<String> = table
                     .iter()
-                    .map(|(k, v)| format!("{} = {}", k, format_value(v, comments, formats, path)))
+                    .map(|(k, v)| {
+                        let value = format_value(v, comments, formats, path);
+                        format!("{} = {}", write_key(std::slice::from_ref(k)), value)
+                    })
                     .collect();
                 format!("{{ {} }}", items.join(", "))
             } else {
-                // Rule 2: Block format for larger or complex tables
-                toml::to_string(value).unwrap().trim().to_string()
+                // Rule 2: Larger or complex tables get their own section, tables left in
+                // value position stay valid as inline tables
+                value.to_string()
             }
         }
         _ => toml::to_string(value).unwrap().trim().to_string(),
@@ -357,7 +721,7 @@ fn format_value(
 }
 
 /// Check if a TOML value is scalar (not a map or array)
-fn is_scalar(value: &toml::Value) -> bool {
+pub(crate) fn is_scalar(value: &toml::Value) -> bool {
     !matches!(value, toml::Value::Table(_) | toml::Value::Array(_))
 }
 
@@ -371,35 +735,34 @@ fn has_comments(
         .any(|k| comments.contains_key(&FieldPath::from_vec(vec![k.clone()])))
 }
 
-/// Recursively flatten nested tables into dotted keys
+/// Recursively flatten nested tables into dotted keys relative to the table `depth`
+/// segments deep
 fn flatten_dotted(
-    result: &mut String,
-    prefix: &str,
+    lines: &mut Vec<KeyLine>,
     table: &toml::map::Map<String, toml::Value>,
-    comments: &HashMap<FieldPath, String>,
-    formats: &HashMap<FieldPath, String>,
+    info: &SchemaInfo,
     path: &FieldPath,
+    depth: usize,
 ) {
     for (key, val) in table {
         let subpath = path.child(key.clone());
-        let dotted_key = format!("{}.{}", prefix, key);
 
         match val {
+            toml::Value::Table(nested) if nested.is_empty() => {
+                lines.push(KeyLine::new(subpath, depth, "{}".to_string()));
+            }
             toml::Value::Table(nested) => {
                 // Recursively flatten nested tables
-                flatten_dotted(result, &dotted_key, nested, comments, formats, &subpath);
+                flatten_dotted(lines, nested, info, &subpath, depth);
             }
             _ => {
                 // Scalar value - write as dotted key
-                append_comment(result, comments, &subpath);
-                result.push_str(&format!(
-                    "{} = {}\n",
-                    dotted_key,
-                    format_value(val, comments, formats, &subpath)
-                ));
+                let value = format_value(val, &info.comments, &info.formats, &subpath);
+                lines.push(KeyLine::new(subpath, depth, value));
             }
         }
     }
+    append_dotted_placeholders(lines, table, info, path, depth);
 }
 
 #[cfg(test)]
@@ -451,6 +814,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_format_value_float() {
         let comments = HashMap::new();
         let formats = HashMap::new();
@@ -511,25 +875,52 @@ mod tests {
     #[test]
     fn test_append_comment() {
         let mut result = String::new();
-        let mut comments = HashMap::new();
+        let mut info = SchemaInfo::default();
         let path = FieldPath::from_vec(vec!["field".to_string()]);
-        comments.insert(path.clone(), "Test comment".to_string());
+        info.comments
+            .insert(path.clone(), "Test comment".to_string());
 
-        append_comment(&mut result, &comments, &path);
+        append_comment(&mut result, &info, &path);
         assert_eq!(result, "# Test comment\n");
     }
 
     #[test]
     fn test_append_comment_multiline() {
         let mut result = String::new();
-        let mut comments = HashMap::new();
+        let mut info = SchemaInfo::default();
         let path = FieldPath::from_vec(vec!["field".to_string()]);
-        comments.insert(path.clone(), "Line 1\nLine 2".to_string());
+        info.comments
+            .insert(path.clone(), "Line 1\nLine 2".to_string());
 
-        append_comment(&mut result, &comments, &path);
+        append_comment(&mut result, &info, &path);
         assert_eq!(result, "# Line 1\n# Line 2\n");
     }
 
+    #[test]
+    fn test_append_comment_strip_markdown() {
+        let mut info = SchemaInfo::default();
+        let path = FieldPath::from_vec(vec!["field".to_string()]);
+        info.comments
+            .insert(path.clone(), "See [`Mode`], *not* `fast`".to_string());
+
+        let mut result = String::new();
+        append_comment(&mut result, &info, &path);
+        assert_eq!(result, "# See [`Mode`], *not* `fast`\n");
+
+        info.strip_markdown = true;
+        let mut result = String::new();
+        append_comment(&mut result, &info, &path);
+        assert_eq!(result, "# See Mode, not fast\n");
+
+        // The unconverted comment is kept in the schema info
+        assert_eq!(info.comments[&path], "See [`Mode`], *not* `fast`");
+        info.trailing_comments.insert(FieldPath::new());
+        assert_eq!(
+            trailing_comment(&info, &path, "field = 1").as_deref(),
+            Some("See Mode, not fast")
+        );
+    }
+
     #[test]
     fn test_append_section_separator() {
         let mut result = String::new();
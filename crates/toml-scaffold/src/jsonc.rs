@@ -0,0 +1,249 @@
+use crate::field_path::FieldPath;
+use crate::format::{append_prefixed_comment, is_scalar, missing_fields};
+use crate::schema::SchemaInfo;
+
+/// Indentation used for each nesting level
+const INDENT: &str = "  ";
+
+/// JSON flavour to emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// JSON with `//` comments and quoted keys
+    Jsonc,
+    /// JSON5 with `//` comments and bare identifier keys
+    Json5,
+}
+
+/// Format TOML value as JSONC/JSON5 with comments at the appropriate paths
+pub fn format_with_comments(value: &toml::Value, info: &SchemaInfo, dialect: Dialect) -> String {
+    let toml::Value::Table(table) = value else {
+        unreachable!()
+    };
+    let mut result = String::new();
+
+    // Root description goes above the opening brace
+    append_prefixed_comment(&mut result, info, &FieldPath::new(), "", "//");
+    result.push_str(&format_object(table, info, &FieldPath::new(), 0, dialect));
+    result
+}
+
+/// An entry of a block object: a value, or the placeholder of a missing field
+enum Entry<'a> {
+    Value(&'a String, &'a toml::Value),
+    Placeholder(&'a FieldPath),
+}
+
+/// Format a table as a block object, one key per line with comments above.
+///
+/// Commas only separate entries. Placeholders before the last value end with a comma and
+/// those after it start with one, so uncommenting them keeps the object valid.
+fn format_object(
+    table: &toml::map::Map<String, toml::Value>,
+    info: &SchemaInfo,
+    path: &FieldPath,
+    depth: usize,
+    dialect: Dialect,
+) -> String {
+    // Keys keep their definition order. Rule 17: missing fields are shown as comments
+    // where they are defined
+    let position = |field: &FieldPath| info.ordered_fields.iter().position(|f| f == field);
+    let mut missing = missing_fields(info, table, path).into_iter().peekable();
+    let mut entries = Vec::new();
+    for (key, val) in table {
+        if let Some(index) = position(&path.child(key.clone())) {
+            while let Some(field) = missing.next_if(|field| position(field) < Some(index)) {
+                entries.push(Entry::Placeholder(field));
+            }
+        }
+        entries.push(Entry::Value(key, val));
+    }
+    entries.extend(missing.map(Entry::Placeholder));
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+
+    let pad = INDENT.repeat(depth + 1);
+    let last_value = entries
+        .iter()
+        .rposition(|entry| matches!(entry, Entry::Value(..)));
+    let mut result = String::from("{\n");
+
+    for (i, entry) in entries.iter().enumerate() {
+        let before_last_value = last_value.is_some_and(|last| i < last);
+        match entry {
+            Entry::Value(key, val) => {
+                let current_path = path.child((*key).clone());
+                append_prefixed_comment(&mut result, info, &current_path, &pad, "//");
+                result.push_str(&format!(
+                    "{}{}: {}{}\n",
+                    pad,
+                    format_key(key, dialect),
+                    format_node(val, info, &current_path, depth + 1, dialect),
+                    if before_last_value { "," } else { "" }
+                ));
+            }
+            Entry::Placeholder(field) => {
+                let key = field.get(path.len()).unwrap();
+                append_prefixed_comment(&mut result, info, field, &pad, "//");
+                let example = info
+                    .examples
+                    .get(field)
+                    .map_or("...".to_string(), |example| format_value(example, dialect));
+                let (leading, trailing) = match last_value {
+                    _ if before_last_value => ("", ","),
+                    // After the last value, or after the first placeholder of an object
+                    // without values
+                    Some(_) => (", ", ""),
+                    None if i > 0 => (", ", ""),
+                    None => ("", ""),
+                };
+                result.push_str(&format!(
+                    "{}// {}{}: {}{}\n",
+                    pad,
+                    leading,
+                    format_key(key, dialect),
+                    example,
+                    trailing
+                ));
+            }
+        }
+    }
+
+    result.push_str(&INDENT.repeat(depth));
+    result.push('}');
+    result
+}
+
+/// Format a value that may span multiple lines
+fn format_node(
+    value: &toml::Value,
+    info: &SchemaInfo,
+    path: &FieldPath,
+    depth: usize,
+    dialect: Dialect,
+) -> String {
+    let format_pref = info.formats.get(path).map(|s| s.as_str());
+
+    match value {
+        toml::Value::Table(table) if format_pref != Some("inline") => {
+            format_object(table, info, path, depth, dialect)
+        }
+        toml::Value::Array(arr)
+            if !arr.is_empty()
+                && (format_pref == Some("multiline") || !arr.iter().all(is_scalar)) =>
+        {
+            // Block array: one element per line
+            let pad = INDENT.repeat(depth + 1);
+            let mut result = String::from("[\n");
+            for (i, item) in arr.iter().enumerate() {
+                result.push_str(&pad);
+                match item {
+                    toml::Value::Table(table) => {
+                        result.push_str(&format_object(table, info, path, depth + 1, dialect))
+                    }
+                    _ => result.push_str(&format_value(item, dialect)),
+                }
+                if i + 1 < arr.len() {
+                    result.push(',');
+                }
+                result.push('\n');
+            }
+            result.push_str(&INDENT.repeat(depth));
+            result.push(']');
+            result
+        }
+        _ => format_value(value, dialect),
+    }
+}
+
+/// Convert TOML value to a single-line JSON representation
+fn format_value(value: &toml::Value, dialect: Dialect) -> String {
+    match value {
+        toml::Value::String(s) => quote(s),
+        toml::Value::Integer(i) => i.to_string(),
+        toml::Value::Float(f) if f.is_finite() => {
+            // Reuse TOML float formatting so whole numbers keep their `.0`
+            use toml_writer::TomlWrite;
+            let mut result = String::new();
+            let _ = result.value(f);
+            result
+        }
+        // JSON has no representation for non-finite numbers
+        toml::Value::Float(_) if dialect == Dialect::Jsonc => "null".to_string(),
+        toml::Value::Float(f) if f.is_nan() => "NaN".to_string(),
+        toml::Value::Float(f) if *f > 0.0 => "Infinity".to_string(),
+        toml::Value::Float(_) => "-Infinity".to_string(),
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Datetime(dt) => quote(&dt.to_string()),
+        toml::Value::Array(arr) => {
+            let items: Vec<String> = arr.iter().map(|v| format_value(v, dialect)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        toml::Value::Table(table) => {
+            if table.is_empty() {
+                return "{}".to_string();
+            }
+            let items: Vec<String> = table
+                .iter()
+                .map(|(k, v)| format!("{}: {}", format_key(k, dialect), format_value(v, dialect)))
+                .collect();
+            format!("{{ {} }}", items.join(", "))
+        }
+    }
+}
+
+/// Format an object key; JSON5 leaves identifier-like keys unquoted
+fn format_key(key: &str, dialect: Dialect) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if dialect == Dialect::Json5 && is_identifier {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// JSON string literal
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_key() {
+        assert_eq!(format_key("host", Dialect::Jsonc), "\"host\"");
+        assert_eq!(format_key("host", Dialect::Json5), "host");
+        assert_eq!(format_key("$ref_1", Dialect::Json5), "$ref_1");
+        assert_eq!(format_key("with-dash", Dialect::Json5), "\"with-dash\"");
+        assert_eq!(format_key("1st", Dialect::Json5), "\"1st\"");
+    }
+
+    #[test]
+    fn test_format_value_non_finite() {
+        let inf = toml::Value::Float(f64::INFINITY);
+        assert_eq!(format_value(&inf, Dialect::Jsonc), "null");
+        assert_eq!(format_value(&inf, Dialect::Json5), "Infinity");
+        let nan = toml::Value::Float(f64::NAN);
+        assert_eq!(format_value(&nan, Dialect::Json5), "NaN");
+    }
+
+    #[test]
+    fn test_format_value_collections() {
+        let arr = toml::Value::Array(vec![toml::Value::Integer(1), toml::Value::Float(2.0)]);
+        assert_eq!(format_value(&arr, Dialect::Jsonc), "[1, 2.0]");
+
+        let mut table = toml::map::Map::new();
+        table.insert("key".to_string(), toml::Value::String("v".to_string()));
+        let table = toml::Value::Table(table);
+        assert_eq!(format_value(&table, Dialect::Jsonc), "{ \"key\": \"v\" }");
+        assert_eq!(format_value(&table, Dialect::Json5), "{ key: \"v\" }");
+    }
+}
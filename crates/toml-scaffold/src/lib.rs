@@ -1,11 +1,42 @@
+mod attributes;
+mod diff;
+mod drift;
+#[cfg(feature = "edit")]
+mod edit;
+mod env;
 mod field_path;
 mod format;
+#[cfg(feature = "jsonc")]
+mod jsonc;
+mod load;
+mod markdown;
+mod migrate;
+mod options;
 mod schema;
+mod validate;
+#[cfg(feature = "yaml")]
+mod yaml;
 
+pub use attributes::FieldAttributes;
+pub use diff::{diff, Change, ConfigDiff, DiffEntry};
+pub use drift::{check_scaffold, DriftError, BLESS_ENV_VAR};
+#[cfg(feature = "edit")]
+pub use edit::{merge_document, rerender, set_value, EditError};
+pub use env::EnvNaming;
 pub use field_path::FieldPath;
+pub use load::{LoadError, Loaded, Loader, Source};
+pub use migrate::{
+    from_str_migrated, latest_version, migrate, Migration, MigrationError, MigrationStep,
+    SCHEMA_VERSION_KEY,
+};
+pub use options::{FieldOrder, ScaffoldOptions};
+pub use schema::SchemaInfo;
 use schemars::JsonSchema;
 use serde::Serialize;
+#[cfg(feature = "edit")]
+pub use toml_edit;
 pub use toml_scaffold_macros::TomlScaffold;
+pub use validate::{validate, Warning};
 
 /// Trait for generating TOML scaffold files with comments from doc strings.
 pub trait TomlScaffold: Serialize + JsonSchema {
@@ -14,11 +45,28 @@ pub trait TomlScaffold: Serialize + JsonSchema {
         std::collections::HashMap::new()
     }
 
-    /// Generates a TOML scaffold string with comments from struct field doc comments.
-    fn to_scaffold(&self) -> Result<String, toml::ser::Error> {
-        // Serialize struct to TOML value
-        let value = toml::Value::try_from(&self)?;
+    /// Returns scaffold-specific field settings declared with `#[scaffold(...)]`
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        std::collections::HashMap::new()
+    }
 
+    /// Returns ordered migrations upgrading older configs to the current format.
+    ///
+    /// When non-empty, scaffolds start with a `schema_version` key holding the latest version.
+    fn migrations() -> Vec<Migration> {
+        Vec::new()
+    }
+
+    /// Returns schema metadata of the values of a map type, `None` for other types.
+    ///
+    /// Map keys are only known from a value, so scaffolds of a map root nest this metadata
+    /// under each key.
+    fn map_value_schema_info() -> Option<SchemaInfo> {
+        None
+    }
+
+    /// Returns schema metadata (comments, field info and format preferences) for this type.
+    fn schema_info() -> SchemaInfo {
         // Extract schema metadata (comments, field info)
         let schema = schemars::schema_for!(Self);
         let mut schema_info = schema::extract_schema_info(&schema, &FieldPath::new());
@@ -26,19 +74,252 @@ pub trait TomlScaffold: Serialize + JsonSchema {
         // Apply format preferences
         schema_info.formats.extend(Self::format_preferences());
 
+        // Scaffold docs replace rustdoc comments
+        for (path, attributes) in Self::field_attributes() {
+            if attributes.hidden {
+                schema_info.hidden.insert(path.clone());
+            }
+            if attributes.commented {
+                schema_info.commented.insert(path.clone());
+            }
+            if attributes.trailing_comments {
+                schema_info.trailing_comments.insert(path.clone());
+            }
+            if attributes.aligned {
+                schema_info.aligned.insert(path.clone());
+            }
+            if let Some(example) = attributes.example {
+                schema_info.examples.insert(path.clone(), example);
+            }
+            if let Some(order) = attributes.order {
+                schema_info.orders.insert(path.clone(), order);
+            }
+            if let Some(group) = attributes.group {
+                schema_info.groups.insert(path.clone(), group);
+            }
+            if let Some(note) = attributes.deprecated {
+                schema_info.deprecated.insert(path.clone(), Some(note));
+            }
+            if let Some(doc) = attributes.doc {
+                schema_info.comments.insert(path, doc);
+            }
+        }
+
+        schema_info
+    }
+
+    /// Generates a TOML scaffold string with comments from struct field doc comments.
+    fn to_scaffold(&self) -> Result<String, toml::ser::Error> {
+        self.to_scaffold_with(&ScaffoldOptions::default())
+    }
+
+    /// Generates a TOML scaffold string using the given options.
+    ///
+    /// Fails if the root value cannot be represented as a TOML document, e.g. a bare
+    /// string or number.
+    fn to_scaffold_with(&self, options: &ScaffoldOptions) -> Result<String, toml::ser::Error> {
+        let (mut value, mut info) = prepare(self, options)?;
+        insert_schema_version::<Self>(&mut value, &mut info);
+
         // Format TOML with comments from schema
-        let result = format::format_with_comments(
-            &value,
-            &schema_info.comments,
-            &schema_info.all_fields,
-            &schema_info.optional_fields,
-            &schema_info.formats,
-            &FieldPath::new(),
-        );
+        let result = format::format_with_comments(&value, &info, &FieldPath::new());
 
         // Rule 14: Always end file with a single newline
         Ok(format!("{}\n", result.trim_end()))
     }
+
+    /// Generates a commented `.env` template with one variable per leaf field.
+    ///
+    /// Elements of arrays of tables get their index as a segment, e.g. `APP__BACKENDS__0__URL`.
+    /// Fails if two fields map to the same variable name, e.g. `a.b` and `a_b`.
+    fn to_env_scaffold(&self, naming: &EnvNaming) -> Result<String, toml::ser::Error> {
+        use serde::ser::Error;
+
+        let (value, info) = prepare(self, &ScaffoldOptions::default())?;
+        let result =
+            env::format_with_comments(&value, &info, naming).map_err(toml::ser::Error::custom)?;
+        Ok(format!("{}\n", result.trim_end()))
+    }
+
+    /// Generates a YAML scaffold string with comments from struct field doc comments.
+    #[cfg(feature = "yaml")]
+    fn to_yaml_scaffold(&self) -> Result<String, toml::ser::Error> {
+        let (value, info) = prepare(self, &ScaffoldOptions::default())?;
+        let result = yaml::format_with_comments(&value, &info, &FieldPath::new(), 0);
+        Ok(format!("{}\n", result.trim_end()))
+    }
+
+    /// Generates a JSON-with-comments scaffold string with `//` comments from struct field doc comments.
+    #[cfg(feature = "jsonc")]
+    fn to_jsonc_scaffold(&self) -> Result<String, toml::ser::Error> {
+        let (value, info) = prepare(self, &ScaffoldOptions::default())?;
+        let result = jsonc::format_with_comments(&value, &info, jsonc::Dialect::Jsonc);
+        Ok(format!("{}\n", result.trim_end()))
+    }
+
+    /// Generates a JSON5 scaffold string with `//` comments from struct field doc comments.
+    #[cfg(feature = "jsonc")]
+    fn to_json5_scaffold(&self) -> Result<String, toml::ser::Error> {
+        let (value, info) = prepare(self, &ScaffoldOptions::default())?;
+        let result = jsonc::format_with_comments(&value, &info, jsonc::Dialect::Json5);
+        Ok(format!("{}\n", result.trim_end()))
+    }
+
+    /// Generates the TOML scaffold as an editable `toml_edit` document.
+    ///
+    /// The rendered scaffold is parsed, so doc comments are kept as decor and the document
+    /// can be edited with [`set_value`] and written back without losing them. It can also be
+    /// merged into a user's document with [`merge_document`], or parts of it re-rendered
+    /// there with [`rerender`].
+    #[cfg(feature = "edit")]
+    fn to_document(&self) -> Result<toml_edit::DocumentMut, toml::ser::Error> {
+        edit::to_document(&self.to_scaffold()?)
+    }
+}
+
+/// Serialize a value and collect its schema metadata for the scaffold backends
+fn prepare<T: TomlScaffold + ?Sized>(
+    value: &T,
+    options: &ScaffoldOptions,
+) -> Result<(toml::Value, SchemaInfo), toml::ser::Error> {
+    use serde::ser::Error;
+
+    // Serialize struct to TOML value
+    let mut value = toml::Value::try_from(value)?;
+
+    // Extract schema metadata (comments, field info, format preferences)
+    let mut info = T::schema_info();
+
+    // Document every entry of a map root like a field of the value type, with the
+    // description of the value type once at the top
+    if let (Some(mut value_info), toml::Value::Table(table)) = (T::map_value_schema_info(), &value)
+    {
+        let description = value_info.comments.remove(&FieldPath::new());
+        for key in table.keys() {
+            let key = FieldPath::from_vec(vec![key.clone()]);
+            info.all_fields.insert(key.clone());
+            info.ordered_fields.push(key.clone());
+            info.extend(value_info.clone().nest_under(&key));
+        }
+        if let Some(description) = description {
+            info.comments.entry(FieldPath::new()).or_insert(description);
+        }
+    }
+
+    info.field_order = options.field_order;
+    info.strip_markdown = options.strip_markdown;
+
+    // The root path enables trailing comments and alignment for every key
+    if options.trailing_comments {
+        info.trailing_comments.insert(FieldPath::new());
+    }
+    if options.aligned {
+        info.aligned.insert(FieldPath::new());
+    }
+
+    // End doc comments with the type, on the same line where trailing comments apply so
+    // single-line docs can still follow the value. Undocumented tables are self-explanatory
+    if options.annotate_types {
+        for (path, annotation) in &info.type_annotations {
+            let is_table =
+                annotation.starts_with("table") || annotation.starts_with("array of tables");
+            let trailing = info.trailing_comments.iter().any(|p| path.starts_with(p));
+            let comment = match info.comments.get(path).map(String::as_str) {
+                None | Some("") if is_table => continue,
+                None | Some("") => format!("type: {}", annotation),
+                Some(doc) if trailing && !doc.contains('\n') => {
+                    format!("{}; type: {}", doc, annotation)
+                }
+                Some(doc) => format!("{}\ntype: {}", doc, annotation),
+            };
+            info.comments.insert(path.clone(), comment);
+        }
+    }
+
+    // Annotate deprecated fields below their doc comment, or leave them out
+    for (path, note) in &info.deprecated {
+        if options.omit_deprecated {
+            info.hidden.insert(path.clone());
+            continue;
+        }
+        let marker = match note {
+            Some(note) => format!("DEPRECATED: {}", note),
+            None => "DEPRECATED".to_string(),
+        };
+        let comment = info.comments.entry(path.clone()).or_default();
+        if !comment.is_empty() {
+            comment.push('\n');
+        }
+        comment.push_str(&marker);
+    }
+
+    // Nest a root array under a key so it renders as `[[key]]` tables, the description of
+    // the element type stays at the top instead of repeating above every table
+    if let toml::Value::Array(array) = value {
+        let key = FieldPath::from_vec(vec![options.root_array_key.clone()]);
+        let description = info.comments.remove(&FieldPath::new());
+        info = info.nest_under(&key);
+        if let Some(description) = description {
+            info.comments.insert(FieldPath::new(), description);
+        }
+        info.all_fields.insert(key.clone());
+        info.ordered_fields.insert(0, key.clone());
+        info.types.insert(key, vec!["array".to_string()]);
+
+        let mut table = toml::Table::new();
+        table.insert(options.root_array_key.clone(), toml::Value::Array(array));
+        value = toml::Value::Table(table);
+    }
+
+    if !value.is_table() {
+        return Err(toml::ser::Error::custom(format!(
+            "cannot generate a scaffold for a {} root, expected a struct, map or array",
+            value.type_str()
+        )));
+    }
+
+    for path in &info.hidden {
+        remove_hidden(&mut value, path.segments());
+    }
+
+    Ok((value, info))
+}
+
+/// Track the schema version as the first key for types with migrations.
+///
+/// Only TOML scaffolds carry it, the other formats are not read back by the migrating
+/// loaders.
+fn insert_schema_version<T: TomlScaffold + ?Sized>(value: &mut toml::Value, info: &mut SchemaInfo) {
+    if let (Some(version), toml::Value::Table(table)) = (latest_version::<T>(), value) {
+        let mut versioned = toml::Table::new();
+        versioned.insert(SCHEMA_VERSION_KEY.to_string(), i64::from(version).into());
+        versioned.extend(std::mem::take(table));
+        *table = versioned;
+        info.comments
+            .entry(FieldPath::from_vec(vec![SCHEMA_VERSION_KEY.to_string()]))
+            .or_insert_with(|| "Config format version, used to upgrade older files".to_string());
+    }
+}
+
+/// Remove a hidden field, from every element when the path crosses an array of tables
+fn remove_hidden(value: &mut toml::Value, segments: &[String]) {
+    match value {
+        toml::Value::Table(table) => match segments {
+            [] => {}
+            [last] => {
+                table.remove(last);
+            }
+            [first, rest @ ..] => {
+                if let Some(child) = table.get_mut(first) {
+                    remove_hidden(child, rest);
+                }
+            }
+        },
+        toml::Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| remove_hidden(item, segments)),
+        _ => {}
+    }
 }
 
 // Implementations for built-in types that return empty format preferences
@@ -56,52 +337,105 @@ macro_rules! impl_toml_scaffold_empty {
 
 // Primitives
 impl_toml_scaffold_empty!(
-    String, &str, bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32,
-    f64,
+    String, str, &str, bool, char, i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize,
+    f32, f64,
 );
 
 // Common types
 impl_toml_scaffold_empty!(serde_json::Value, std::path::PathBuf,);
 
-// Generic collections
+/// Format preferences of the elements of a collection, so generic collections format their
+/// elements like `T`. The format of the element type itself describes its own table, not the
+/// collection, so it is left out
+fn element_format_preferences<T: TomlScaffold>() -> std::collections::HashMap<FieldPath, String> {
+    let mut preferences = T::format_preferences();
+    preferences.remove(&FieldPath::new());
+    preferences
+}
+
 impl<T: TomlScaffold> TomlScaffold for Vec<T> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
-        std::collections::HashMap::new()
+        element_format_preferences::<T>()
+    }
+
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        T::field_attributes()
     }
 }
 
 impl<T: TomlScaffold> TomlScaffold for Option<T> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
-        std::collections::HashMap::new()
+        T::format_preferences()
+    }
+
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        T::field_attributes()
     }
 }
 
+// Map keys are not part of the type, so the metadata of `V` only applies to map roots
 impl<K: Serialize + JsonSchema, V: TomlScaffold> TomlScaffold for std::collections::HashMap<K, V> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
         std::collections::HashMap::new()
     }
+
+    fn map_value_schema_info() -> Option<SchemaInfo> {
+        Some(V::schema_info())
+    }
 }
 
 impl<K: Serialize + JsonSchema, V: TomlScaffold> TomlScaffold for std::collections::BTreeMap<K, V> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
         std::collections::HashMap::new()
     }
+
+    fn map_value_schema_info() -> Option<SchemaInfo> {
+        Some(V::schema_info())
+    }
 }
 
 impl<T: TomlScaffold> TomlScaffold for std::collections::HashSet<T> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
-        std::collections::HashMap::new()
+        element_format_preferences::<T>()
+    }
+
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        T::field_attributes()
     }
 }
 
 impl<T: TomlScaffold> TomlScaffold for std::collections::BTreeSet<T> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
-        std::collections::HashMap::new()
+        element_format_preferences::<T>()
+    }
+
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        T::field_attributes()
     }
 }
 
 impl<T: TomlScaffold> TomlScaffold for Box<T> {
+    fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
+        T::format_preferences()
+    }
+
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        T::field_attributes()
+    }
+}
+
+impl<T: ?Sized> TomlScaffold for std::marker::PhantomData<T> {
     fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
         std::collections::HashMap::new()
     }
 }
+
+impl<T: TomlScaffold + ToOwned + ?Sized> TomlScaffold for std::borrow::Cow<'_, T> {
+    fn format_preferences() -> std::collections::HashMap<FieldPath, String> {
+        T::format_preferences()
+    }
+
+    fn field_attributes() -> std::collections::HashMap<FieldPath, FieldAttributes> {
+        T::field_attributes()
+    }
+}
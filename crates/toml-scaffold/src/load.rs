@@ -0,0 +1,555 @@
+use crate::env::EnvNaming;
+use crate::field_path::FieldPath;
+use crate::migrate::{migrate, remove_version, MigrationError};
+use crate::schema::SchemaInfo;
+use crate::validate::{self, Warning};
+use crate::TomlScaffold;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+/// Where a loaded configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Not set anywhere, filled in by the type's serde defaults
+    Default,
+    /// Read from the TOML document
+    File,
+    /// Overridden by the named environment variable
+    Env(String),
+    /// Overridden by a `--set key=value` argument
+    Cli,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => f.write_str("default"),
+            Source::File => f.write_str("file"),
+            Source::Env(name) => write!(f, "env {}", name),
+            Source::Cli => f.write_str("--set"),
+        }
+    }
+}
+
+/// Error returned by [`Loader::load`].
+#[derive(Debug)]
+pub enum LoadError {
+    /// The configuration file could not be read
+    Io {
+        /// Path of the file
+        path: PathBuf,
+        /// Underlying I/O error
+        error: std::io::Error,
+    },
+    /// The TOML document could not be parsed
+    Parse(toml::de::Error),
+    /// The TOML document could not be upgraded to the latest schema version
+    Migration(MigrationError),
+    /// An environment variable or `--set` override could not be applied
+    Override {
+        /// Origin of the override
+        source: Source,
+        /// What went wrong
+        message: String,
+    },
+    /// The merged configuration does not match the target type
+    Deserialize(toml::de::Error),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io { path, error } => {
+                write!(f, "failed to read {}: {}", path.display(), error)
+            }
+            LoadError::Parse(error) => write!(f, "failed to parse TOML: {}", error),
+            LoadError::Migration(error) => write!(f, "failed to migrate config: {}", error),
+            LoadError::Override { source, message } => {
+                write!(f, "invalid override from {}: {}", source, message)
+            }
+            LoadError::Deserialize(error) => write!(f, "invalid configuration: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { error, .. } => Some(error),
+            LoadError::Parse(error) | LoadError::Deserialize(error) => Some(error),
+            LoadError::Migration(error) => Some(error),
+            LoadError::Override { .. } => None,
+        }
+    }
+}
+
+/// A loaded configuration together with the origin of each leaf value.
+#[derive(Debug)]
+pub struct Loaded<T> {
+    /// The deserialized configuration
+    pub value: T,
+    /// Origin of each leaf field path of the final configuration
+    pub sources: HashMap<FieldPath, Source>,
+    /// Problems found in the TOML document, such as deprecated fields
+    pub warnings: Vec<Warning>,
+}
+
+impl<T> Loaded<T> {
+    /// Returns the origin of the value at the given path.
+    pub fn source(&self, path: &FieldPath) -> Option<&Source> {
+        self.sources.get(path)
+    }
+}
+
+/// Loads a configuration from TOML, then applies environment variable and `--set` overrides.
+///
+/// The TOML document is first upgraded with the type's [`TomlScaffold::migrations`].
+/// Overrides are applied in order of increasing precedence: TOML document, environment
+/// variables, then `--set` arguments. Override values are coerced using the schema type
+/// of the addressed field, so `APP__PORT=8080` sets an integer and `APP__NAME=8080` a string.
+pub struct Loader<T> {
+    file: Option<PathBuf>,
+    document: Option<String>,
+    env: Option<EnvOverrides>,
+    sets: Vec<String>,
+    marker: PhantomData<T>,
+}
+
+/// Environment variables to read overrides from
+struct EnvOverrides {
+    naming: EnvNaming,
+    /// Explicit variables, or `None` for the process environment
+    vars: Option<Vec<(String, String)>>,
+}
+
+impl<T: TomlScaffold + DeserializeOwned> Loader<T> {
+    /// Creates a loader with no sources, which deserializes from an empty document.
+    pub fn new() -> Self {
+        Self {
+            file: None,
+            document: None,
+            env: None,
+            sets: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Reads the TOML document from a file.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self.document = None;
+        self
+    }
+
+    /// Uses the given string as the TOML document.
+    pub fn document(mut self, document: impl Into<String>) -> Self {
+        self.document = Some(document.into());
+        self.file = None;
+        self
+    }
+
+    /// Applies overrides from the process environment using the given naming convention.
+    pub fn env(mut self, naming: EnvNaming) -> Self {
+        self.env = Some(EnvOverrides { naming, vars: None });
+        self
+    }
+
+    /// Applies overrides from the given variables instead of the process environment.
+    pub fn env_vars<K, V>(
+        mut self,
+        naming: EnvNaming,
+        vars: impl IntoIterator<Item = (K, V)>,
+    ) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars = vars
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        self.env = Some(EnvOverrides {
+            naming,
+            vars: Some(vars),
+        });
+        self
+    }
+
+    /// Adds a `key=value` override where `key` is a TOML dotted key, e.g. `server.port=8080`.
+    pub fn set(mut self, assignment: impl Into<String>) -> Self {
+        self.sets.push(assignment.into());
+        self
+    }
+
+    /// Adds several `key=value` overrides, applied in order.
+    pub fn sets<S: Into<String>>(mut self, assignments: impl IntoIterator<Item = S>) -> Self {
+        self.sets.extend(assignments.into_iter().map(Into::into));
+        self
+    }
+
+    /// Loads, merges and deserializes the configuration.
+    pub fn load(self) -> Result<Loaded<T>, LoadError> {
+        let info = T::schema_info();
+        let mut sources = HashMap::new();
+
+        let document = match (&self.file, self.document) {
+            (Some(path), _) => std::fs::read_to_string(path).map_err(|error| LoadError::Io {
+                path: path.clone(),
+                error,
+            })?,
+            (None, Some(document)) => document,
+            (None, None) => String::new(),
+        };
+        let mut table: toml::Table = toml::from_str(&document).map_err(LoadError::Parse)?;
+        migrate::<T>(&mut table).map_err(LoadError::Migration)?;
+        remove_version::<T>(&mut table);
+        let warnings = validate::warnings(&table, &info);
+        let mut value = toml::Value::Table(table);
+        mark_sources(&mut sources, &FieldPath::new(), &value, &Source::File);
+
+        if let Some(EnvOverrides { naming, vars }) = self.env {
+            let vars: HashMap<String, String> = match vars {
+                Some(vars) => vars.into_iter().collect(),
+                // Variables that are not valid Unicode cannot match a field name
+                None => std::env::vars_os()
+                    .filter_map(|(name, value)| {
+                        Some((name.into_string().ok()?, value.into_string().ok()?))
+                    })
+                    .collect(),
+            };
+
+            // Candidate paths are schema fields plus whatever the document already contains
+            let mut candidates: Vec<FieldPath> = info.all_fields.iter().cloned().collect();
+            collect_paths(&value, &FieldPath::new(), &mut candidates);
+            // Parents first so that nested variables refine a table set by a shorter one
+            candidates.sort_by_key(|path| (path.len(), path.to_string()));
+            candidates.dedup();
+
+            for path in candidates {
+                let name = naming.var_name(&path);
+                if let Some(raw) = vars.get(&name) {
+                    let source = Source::Env(name);
+                    apply_override(&mut value, &info, &path, raw, &source, &mut sources)?;
+                }
+            }
+        }
+
+        for assignment in &self.sets {
+            let (key, raw) = split_assignment(assignment).ok_or_else(|| LoadError::Override {
+                source: Source::Cli,
+                message: format!("expected `key=value`, got `{}`", assignment),
+            })?;
+            let path: FieldPath = key.trim().parse().map_err(|e| LoadError::Override {
+                source: Source::Cli,
+                message: format!("invalid key `{}`: {}", key.trim(), e),
+            })?;
+            apply_override(
+                &mut value,
+                &info,
+                &path,
+                raw.trim(),
+                &Source::Cli,
+                &mut sources,
+            )?;
+        }
+
+        let loaded: T = value.try_into().map_err(LoadError::Deserialize)?;
+
+        // Whatever the merged document did not provide came from serde defaults
+        if let Ok(serialized) = toml::Value::try_from(&loaded) {
+            let mut defaults = HashMap::new();
+            mark_sources(
+                &mut defaults,
+                &FieldPath::new(),
+                &serialized,
+                &Source::Default,
+            );
+            for (path, source) in defaults {
+                sources.entry(path).or_insert(source);
+            }
+        }
+
+        Ok(Loaded {
+            value: loaded,
+            sources,
+            warnings,
+        })
+    }
+}
+
+impl<T: TomlScaffold + DeserializeOwned> Default for Loader<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Split `key=value` at the first `=` outside a quoted key
+fn split_assignment(assignment: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (i, c) in assignment.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '=') => return Some((&assignment[..i], &assignment[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Coerce a raw override and store it at `path`, recording its source
+fn apply_override(
+    value: &mut toml::Value,
+    info: &SchemaInfo,
+    path: &FieldPath,
+    raw: &str,
+    source: &Source,
+    sources: &mut HashMap<FieldPath, Source>,
+) -> Result<(), LoadError> {
+    let error = |message: String| LoadError::Override {
+        source: source.clone(),
+        message,
+    };
+
+    let types = info.types.get(&schema_path(value, path));
+    let coerced = coerce(raw, types.map(|t| t.as_slice()))
+        .map_err(|message| error(format!("{}: {}", path, message)))?;
+    set_path(value, path, coerced.clone()).map_err(error)?;
+    mark_sources(sources, path, &coerced, source);
+    Ok(())
+}
+
+/// Convert a raw string to a TOML value of the field's schema type
+fn coerce(raw: &str, types: Option<&[String]>) -> Result<toml::Value, String> {
+    let Some(types) = types else {
+        // Unknown type: accept any TOML literal, otherwise keep the raw string
+        return Ok(parse_literal(raw).unwrap_or_else(|_| toml::Value::String(raw.to_string())));
+    };
+
+    let mut last_error = format!("cannot convert `{}`", raw);
+    for ty in types {
+        let parsed = match ty.as_str() {
+            "boolean" => raw
+                .trim()
+                .parse::<bool>()
+                .map(toml::Value::Boolean)
+                .map_err(|e| e.to_string()),
+            "integer" => raw
+                .trim()
+                .parse::<i64>()
+                .map(toml::Value::Integer)
+                .map_err(|e| e.to_string()),
+            "number" => raw
+                .trim()
+                .parse::<f64>()
+                .map(toml::Value::Float)
+                .map_err(|e| e.to_string()),
+            "array" | "object" => parse_literal(raw),
+            // Strings are the fallback for mixed types, try the others first
+            _ => continue,
+        };
+        match parsed {
+            Ok(value) => return Ok(value),
+            Err(e) => last_error = format!("expected {}, got `{}` ({})", ty, raw, e),
+        }
+    }
+
+    if types.iter().any(|t| t == "string") {
+        Ok(toml::Value::String(raw.to_string()))
+    } else {
+        Err(last_error)
+    }
+}
+
+/// Parse a TOML value literal such as `[1, 2]` or `{ a = 1 }`
+fn parse_literal(raw: &str) -> Result<toml::Value, String> {
+    let mut table: toml::Table =
+        toml::from_str(&format!("value = {}", raw)).map_err(|e| e.message().to_string())?;
+    Ok(table.remove("value").unwrap())
+}
+
+/// The schema path of `path`, without the indices of array elements
+fn schema_path(value: &toml::Value, path: &FieldPath) -> FieldPath {
+    let mut result = FieldPath::new();
+    let mut current = Some(value);
+    for segment in path.segments() {
+        current = match current {
+            Some(toml::Value::Array(items)) => {
+                segment.parse::<usize>().ok().and_then(|i| items.get(i))
+            }
+            other => {
+                result.push(segment.clone());
+                other.and_then(|value| value.get(segment))
+            }
+        };
+    }
+    result
+}
+
+/// Store `new_value` at `path`, creating intermediate tables as needed.
+///
+/// Segments below an array index its existing elements, e.g. `backends.0.url`.
+fn set_path(
+    value: &mut toml::Value,
+    path: &FieldPath,
+    new_value: toml::Value,
+) -> Result<(), String> {
+    let Some((last, parents)) = path.segments().split_last() else {
+        return Err("empty key".to_string());
+    };
+
+    let mut current = value;
+    for (i, segment) in parents.iter().enumerate() {
+        let parent = || FieldPath::from_vec(parents[..i].to_vec());
+        current = match current {
+            toml::Value::Table(table) => table
+                .entry(segment.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new())),
+            toml::Value::Array(items) => {
+                let len = items.len();
+                segment
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| items.get_mut(i))
+                    .ok_or_else(|| {
+                        format!(
+                            "`{}` has no element `{}` ({} elements)",
+                            parent(),
+                            segment,
+                            len
+                        )
+                    })?
+            }
+            _ => return Err(format!("`{}` is not a table", parent())),
+        };
+    }
+
+    match current {
+        toml::Value::Table(table) => {
+            table.insert(last.clone(), new_value);
+            Ok(())
+        }
+        _ => Err(format!(
+            "`{}` is not a table",
+            FieldPath::from_vec(parents.to_vec())
+        )),
+    }
+}
+
+/// Record `source` for every leaf under `path`, replacing earlier sources
+fn mark_sources(
+    sources: &mut HashMap<FieldPath, Source>,
+    path: &FieldPath,
+    value: &toml::Value,
+    source: &Source,
+) {
+    sources.retain(|existing, _| !existing.starts_with(path));
+    let mut leaves = Vec::new();
+    collect_leaves(value, path, &mut leaves);
+    for leaf in leaves {
+        sources.insert(leaf, source.clone());
+    }
+}
+
+/// Collect paths of non-table values (arrays count as leaves)
+fn collect_leaves(value: &toml::Value, path: &FieldPath, leaves: &mut Vec<FieldPath>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, val) in table {
+                collect_leaves(val, &path.child(key.clone()), leaves);
+            }
+        }
+        _ => leaves.push(path.clone()),
+    }
+}
+
+/// Collect every key path in a value, including tables and elements of arrays of tables
+fn collect_paths(value: &toml::Value, path: &FieldPath, paths: &mut Vec<FieldPath>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, val) in table {
+                let current_path = path.child(key.clone());
+                paths.push(current_path.clone());
+                collect_paths(val, &current_path, paths);
+            }
+        }
+        toml::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate().filter(|(_, item)| item.is_table()) {
+                collect_paths(item, &path.child(i.to_string()), paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_coerce_by_type() {
+        assert_eq!(
+            coerce("8080", Some(&types(&["integer"]))),
+            Ok(toml::Value::Integer(8080))
+        );
+        assert_eq!(
+            coerce("8080", Some(&types(&["string"]))),
+            Ok(toml::Value::String("8080".to_string()))
+        );
+        assert_eq!(
+            coerce("true", Some(&types(&["boolean", "null"]))),
+            Ok(toml::Value::Boolean(true))
+        );
+        assert_eq!(
+            coerce("[1, 2]", Some(&types(&["array"]))),
+            Ok(toml::Value::Array(vec![1.into(), 2.into()]))
+        );
+        assert!(coerce("fast", Some(&types(&["integer"]))).is_err());
+    }
+
+    #[test]
+    fn test_coerce_unknown_type() {
+        assert_eq!(coerce("1.5", None), Ok(toml::Value::Float(1.5)));
+        assert_eq!(
+            coerce("hello world", None),
+            Ok(toml::Value::String("hello world".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_split_assignment() {
+        assert_eq!(split_assignment("a.b=1"), Some(("a.b", "1")));
+        assert_eq!(split_assignment("a=b=c"), Some(("a", "b=c")));
+        assert_eq!(split_assignment("\"x=y\".z=1"), Some(("\"x=y\".z", "1")));
+        assert_eq!(split_assignment("novalue"), None);
+    }
+
+    #[test]
+    fn test_set_path() {
+        let mut value = toml::Value::Table(toml::Table::new());
+        let path: FieldPath = "a.b".parse().unwrap();
+        set_path(&mut value, &path, 1.into()).unwrap();
+        assert_eq!(value["a"]["b"], toml::Value::Integer(1));
+
+        let path: FieldPath = "a.b.c".parse().unwrap();
+        assert!(set_path(&mut value, &path, 2.into()).is_err());
+    }
+
+    #[test]
+    fn test_set_path_in_array() {
+        let mut value: toml::Value = toml::from_str("items = [{ a = 1 }]").unwrap();
+        let path: FieldPath = "items.0.a".parse().unwrap();
+        assert_eq!(schema_path(&value, &path), "items.a".parse().unwrap());
+        set_path(&mut value, &path, 2.into()).unwrap();
+        assert_eq!(value["items"][0]["a"], toml::Value::Integer(2));
+
+        let path: FieldPath = "items.1.a".parse().unwrap();
+        assert!(set_path(&mut value, &path, 3.into()).is_err());
+    }
+}
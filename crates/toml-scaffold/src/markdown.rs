@@ -0,0 +1,184 @@
+/// Convert rustdoc markdown to plain text for config comments.
+///
+/// Strips link syntax, inline code backticks, emphasis, heading markers, code fences and
+/// link reference definitions. Line structure, and therefore lists, is kept.
+pub(crate) fn to_plain_text(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            // Code blocks are kept verbatim
+            lines.push(line.to_string());
+            continue;
+        }
+        if is_reference_definition(trimmed) {
+            continue;
+        }
+
+        let line = strip_heading(line);
+        lines.push(strip_inline(line));
+    }
+
+    // Removed lines may leave blank lines at the edges
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+/// `[name]: target` lines used by reference-style links
+fn is_reference_definition(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]:")
+}
+
+/// Remove ATX heading markers (`# Title`)
+fn strip_heading(line: &str) -> &str {
+    let trimmed = line.trim_start_matches('#');
+    if trimmed.len() < line.len() && (trimmed.is_empty() || trimmed.starts_with(' ')) {
+        trimmed.trim_start()
+    } else {
+        line
+    }
+}
+
+/// Remove links, backticks and emphasis within a line
+fn strip_inline(line: &str) -> String {
+    let mut result = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(['[', '`', '*', '_']) {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("**") {
+            // **strong**
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('`') {
+            // `code` keeps its content verbatim
+            match after.find('`') {
+                Some(end) => {
+                    result.push_str(&after[..end]);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    result.push('`');
+                    rest = after;
+                }
+            }
+        } else if let Some(after) = rest.strip_prefix('[') {
+            match after.find(']') {
+                Some(end) if is_link(&after[..end], &after[end + 1..]) => {
+                    // Link text may contain code spans, e.g. [`Foo`]
+                    result.push_str(&strip_inline(&after[..end]));
+                    rest = skip_link_target(&after[end + 1..]);
+                }
+                _ => {
+                    result.push('[');
+                    rest = after;
+                }
+            }
+        } else {
+            // *emphasis* or _emphasis_, otherwise a list marker or plain text such as snake_case
+            let marker = &rest[..1];
+            let after = &rest[1..];
+            let opens = !result.ends_with(|c: char| c.is_alphanumeric());
+            match emphasis_end(after, marker).filter(|_| opens) {
+                Some(end) => {
+                    result.push_str(&strip_inline(&after[..end]));
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    result.push_str(marker);
+                    rest = after;
+                }
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Position of the marker closing an emphasis span, which must hug the text and end a word
+fn emphasis_end(text: &str, marker: &str) -> Option<usize> {
+    if text.starts_with(char::is_whitespace) {
+        return None;
+    }
+    text.match_indices(marker).map(|(i, _)| i).find(|&i| {
+        i > 0
+            && !text[..i].ends_with(char::is_whitespace)
+            && !text[i + 1..].starts_with(|c: char| c.is_alphanumeric())
+    })
+}
+
+/// Brackets form a link when followed by a target or when they wrap an intra-doc path
+fn is_link(text: &str, rest: &str) -> bool {
+    let is_path = |s: &str| {
+        !s.is_empty()
+            && !s.starts_with(|c: char| c.is_ascii_digit())
+            && s.chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == ':')
+    };
+    rest.starts_with(['(', '[']) || (text.starts_with('`') && text.ends_with('`')) || is_path(text)
+}
+
+/// Skip `(url)` or `[ref]` following link text
+fn skip_link_target(rest: &str) -> &str {
+    for (open, close) in [('(', ')'), ('[', ']')] {
+        if let Some(after) = rest.strip_prefix(open) {
+            if let Some(end) = after.find(close) {
+                return &after[end + 1..];
+            }
+        }
+    }
+    rest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_and_code() {
+        assert_eq!(
+            to_plain_text(
+                "See [`Backend`], [Mode], [the docs](https://example.com) or [`Foo::bar`][ref]"
+            ),
+            "See Backend, Mode, the docs or Foo::bar"
+        );
+        assert_eq!(to_plain_text("Use `**` **carefully**"), "Use ** carefully");
+    }
+
+    #[test]
+    fn test_headings_fences_and_lists() {
+        let doc = "# Modes\n\n* `fast` - skip checks\n* `safe`\n\n```toml\nmode = \"fast\"\n```\n\n[ref]: crate::Mode";
+        assert_eq!(
+            to_plain_text(doc),
+            "Modes\n\n* fast - skip checks\n* safe\n\nmode = \"fast\""
+        );
+    }
+
+    #[test]
+    fn test_emphasis() {
+        assert_eq!(
+            to_plain_text("Use *fast* mode, _not_ the *safe `default`*"),
+            "Use fast mode, not the safe default"
+        );
+        assert_eq!(to_plain_text("**Very** _important_."), "Very important.");
+    }
+
+    #[test]
+    fn test_plain_text_unchanged() {
+        assert_eq!(
+            to_plain_text("Port [1-65535], #1 choice"),
+            "Port [1-65535], #1 choice"
+        );
+        assert_eq!(
+            to_plain_text("Set max_conn_count to 2*3*4, a * b or _"),
+            "Set max_conn_count to 2*3*4, a * b or _"
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use crate::field_path::FieldPath;
+use crate::TomlScaffold;
+use serde::de::DeserializeOwned;
+
+/// Key holding the schema version of a config file.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// A single transformation of a parsed config, addressed by field paths.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MigrationStep {
+    /// Rename the last segment of a path, keeping the value in the same table
+    Rename {
+        /// Current path of the value
+        from: FieldPath,
+        /// New key name
+        to: String,
+    },
+    /// Move a value or a whole subtree to another path, replacing what is there
+    Move {
+        /// Current path of the value
+        from: FieldPath,
+        /// New path of the value
+        to: FieldPath,
+    },
+    /// Set a value if nothing is set at the path yet
+    SetDefault {
+        /// Path of the value
+        path: FieldPath,
+        /// Value to insert
+        value: toml::Value,
+    },
+    /// Remove a value or a whole subtree
+    Drop(FieldPath),
+}
+
+/// Ordered steps upgrading a config to a schema version.
+///
+/// Steps whose source path is missing are skipped, so a migration also applies to
+/// configs that never set the affected fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    /// Schema version of the config after this migration
+    pub version: u32,
+    /// Steps applied in order
+    pub steps: Vec<MigrationStep>,
+}
+
+impl Migration {
+    /// Creates an empty migration to the given schema version.
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Renames the value at dotted key `from` to `to` within the same table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is not a valid dotted key.
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.steps.push(MigrationStep::Rename {
+            from: parse_key(from),
+            to: to.to_string(),
+        });
+        self
+    }
+
+    /// Moves the value or subtree at dotted key `from` to dotted key `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` or `to` is not a valid dotted key.
+    pub fn move_to(mut self, from: &str, to: &str) -> Self {
+        self.steps.push(MigrationStep::Move {
+            from: parse_key(from),
+            to: parse_key(to),
+        });
+        self
+    }
+
+    /// Sets the value at dotted key `path` unless it is already set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid dotted key.
+    pub fn set_default(mut self, path: &str, value: impl Into<toml::Value>) -> Self {
+        self.steps.push(MigrationStep::SetDefault {
+            path: parse_key(path),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// Removes the value or subtree at dotted key `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a valid dotted key.
+    pub fn drop_key(mut self, path: &str) -> Self {
+        self.steps.push(MigrationStep::Drop(parse_key(path)));
+        self
+    }
+}
+
+/// Error returned when migrating a config.
+#[derive(Debug)]
+pub enum MigrationError {
+    /// The config could not be parsed
+    Parse(toml::de::Error),
+    /// The `schema_version` key is not a non-negative integer
+    InvalidVersion(toml::Value),
+    /// The config is newer than the latest known migration
+    UnsupportedVersion {
+        /// Version found in the config
+        found: u32,
+        /// Latest version known to the type
+        latest: u32,
+    },
+    /// A migration step could not be applied
+    Step {
+        /// Version of the failing migration
+        version: u32,
+        /// What went wrong
+        message: String,
+    },
+    /// The migrated config does not match the target type
+    Deserialize(toml::de::Error),
+}
+
+impl std::fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrationError::Parse(error) => write!(f, "failed to parse TOML: {}", error),
+            MigrationError::InvalidVersion(value) => {
+                write!(f, "invalid {}: {}", SCHEMA_VERSION_KEY, value)
+            }
+            MigrationError::UnsupportedVersion { found, latest } => write!(
+                f,
+                "{} {} is newer than the latest supported version {}",
+                SCHEMA_VERSION_KEY, found, latest
+            ),
+            MigrationError::Step { version, message } => {
+                write!(f, "migration to version {} failed: {}", version, message)
+            }
+            MigrationError::Deserialize(error) => write!(f, "invalid configuration: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrationError::Parse(error) | MigrationError::Deserialize(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the latest schema version of `T`, or `None` if it declares no migrations.
+pub fn latest_version<T: TomlScaffold + ?Sized>() -> Option<u32> {
+    T::migrations().iter().map(|m| m.version).max()
+}
+
+/// Upgrades a parsed config to the latest schema version of `T` in place.
+///
+/// Configs without a `schema_version` key are treated as version 0. Returns the
+/// version the config had before migrating.
+pub fn migrate<T: TomlScaffold + ?Sized>(table: &mut toml::Table) -> Result<u32, MigrationError> {
+    let found = match table.get(SCHEMA_VERSION_KEY) {
+        None => 0,
+        Some(toml::Value::Integer(v)) => {
+            u32::try_from(*v).map_err(|_| MigrationError::InvalidVersion((*v).into()))?
+        }
+        Some(other) => return Err(MigrationError::InvalidVersion(other.clone())),
+    };
+    let Some(latest) = latest_version::<T>() else {
+        return Ok(found);
+    };
+    if found > latest {
+        return Err(MigrationError::UnsupportedVersion { found, latest });
+    }
+
+    let mut migrations = T::migrations();
+    migrations.sort_by_key(|m| m.version);
+    for migration in migrations.iter().filter(|m| m.version > found) {
+        for step in &migration.steps {
+            apply_step(table, step).map_err(|message| MigrationError::Step {
+                version: migration.version,
+                message,
+            })?;
+        }
+    }
+    table.insert(SCHEMA_VERSION_KEY.to_string(), i64::from(latest).into());
+    Ok(found)
+}
+
+/// Parses a config, upgrades it to the latest schema version and deserializes it.
+///
+/// The `schema_version` key is not passed on to `T`, unless `T` declares it as a field.
+pub fn from_str_migrated<T: TomlScaffold + DeserializeOwned>(s: &str) -> Result<T, MigrationError> {
+    let mut table: toml::Table = toml::from_str(s).map_err(MigrationError::Parse)?;
+    migrate::<T>(&mut table)?;
+    remove_version::<T>(&mut table);
+    toml::Value::Table(table)
+        .try_into()
+        .map_err(MigrationError::Deserialize)
+}
+
+/// Remove the `schema_version` key before deserializing, so types denying unknown fields
+/// still load. Types with their own `schema_version` field keep it
+pub(crate) fn remove_version<T: TomlScaffold + ?Sized>(table: &mut toml::Table) {
+    let key = FieldPath::from_vec(vec![SCHEMA_VERSION_KEY.to_string()]);
+    if !T::schema_info().all_fields.contains(&key) {
+        table.remove(SCHEMA_VERSION_KEY);
+    }
+}
+
+/// Apply one step to the config
+fn apply_step(table: &mut toml::Table, step: &MigrationStep) -> Result<(), String> {
+    match step {
+        MigrationStep::Rename { from, to } => {
+            let mut target = from.segments()[..from.len() - 1].to_vec();
+            target.push(to.clone());
+            move_value(table, from, &FieldPath::from_vec(target))
+        }
+        MigrationStep::Move { from, to } => move_value(table, from, to),
+        MigrationStep::SetDefault { path, value } => {
+            if get(table, path).is_none() {
+                insert(table, path, value.clone())?;
+            }
+            Ok(())
+        }
+        MigrationStep::Drop(path) => {
+            remove(table, path);
+            Ok(())
+        }
+    }
+}
+
+/// Move the value at `from` to `to`, skipping missing sources
+fn move_value(table: &mut toml::Table, from: &FieldPath, to: &FieldPath) -> Result<(), String> {
+    if let Some(value) = remove(table, from) {
+        insert(table, to, value)?;
+    }
+    Ok(())
+}
+
+/// Look up the value at a path
+fn get<'a>(table: &'a toml::Table, path: &FieldPath) -> Option<&'a toml::Value> {
+    let (last, parents) = path.segments().split_last()?;
+    let mut current = table;
+    for segment in parents {
+        current = current.get(segment)?.as_table()?;
+    }
+    current.get(last)
+}
+
+/// Remove and return the value at a path
+fn remove(table: &mut toml::Table, path: &FieldPath) -> Option<toml::Value> {
+    let (last, parents) = path.segments().split_last()?;
+    let mut current = table;
+    for segment in parents {
+        current = current.get_mut(segment)?.as_table_mut()?;
+    }
+    current.remove(last)
+}
+
+/// Insert a value at a path, creating intermediate tables
+fn insert(table: &mut toml::Table, path: &FieldPath, value: toml::Value) -> Result<(), String> {
+    let Some((last, parents)) = path.segments().split_last() else {
+        return Err("empty key".to_string());
+    };
+    let mut current = table;
+    for segment in parents {
+        current = current
+            .entry(segment.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| format!("cannot insert `{}`: `{}` is not a table", path, segment))?;
+    }
+    current.insert(last.clone(), value);
+    Ok(())
+}
+
+/// Parse a dotted key declared in a migration
+fn parse_key(key: &str) -> FieldPath {
+    key.parse()
+        .unwrap_or_else(|e| panic!("invalid dotted key `{}` in migration: {}", key, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(s: &str) -> toml::Table {
+        toml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut config = table("[server]\naddr = \"x\"");
+        let step = MigrationStep::Rename {
+            from: parse_key("server.addr"),
+            to: "host".to_string(),
+        };
+        apply_step(&mut config, &step).unwrap();
+        assert_eq!(config, table("[server]\nhost = \"x\""));
+    }
+
+    #[test]
+    fn test_move_subtree() {
+        let mut config = table("[db]\nurl = \"x\"\n[db.pool]\nsize = 4");
+        let step = MigrationStep::Move {
+            from: parse_key("db.pool"),
+            to: parse_key("pool"),
+        };
+        apply_step(&mut config, &step).unwrap();
+        assert_eq!(config, table("[db]\nurl = \"x\"\n[pool]\nsize = 4"));
+    }
+
+    #[test]
+    fn test_move_missing_source_is_skipped() {
+        let mut config = table("a = 1");
+        let step = MigrationStep::Move {
+            from: parse_key("missing"),
+            to: parse_key("b"),
+        };
+        apply_step(&mut config, &step).unwrap();
+        assert_eq!(config, table("a = 1"));
+    }
+
+    #[test]
+    fn test_move_into_non_table() {
+        let mut config = table("a = 1\nb = 2");
+        let step = MigrationStep::Move {
+            from: parse_key("b"),
+            to: parse_key("a.b"),
+        };
+        assert!(apply_step(&mut config, &step).is_err());
+    }
+
+    #[test]
+    fn test_set_default_and_drop() {
+        let mut config = table("a = 1");
+        let set = |path: &str, value: i64| MigrationStep::SetDefault {
+            path: parse_key(path),
+            value: value.into(),
+        };
+        apply_step(&mut config, &set("a", 2)).unwrap();
+        apply_step(&mut config, &set("t.b", 3)).unwrap();
+        apply_step(&mut config, &MigrationStep::Drop(parse_key("a"))).unwrap();
+        assert_eq!(config, table("[t]\nb = 3"));
+    }
+}
@@ -0,0 +1,56 @@
+/// Options controlling how a scaffold is generated.
+///
+/// Construct with struct update syntax to override single options:
+///
+/// ```
+/// use toml_scaffold::ScaffoldOptions;
+///
+/// let options = ScaffoldOptions {
+///     root_array_key: "servers".to_string(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScaffoldOptions {
+    /// Key holding the elements when the root value is an array, rendered as `[[key]]`
+    pub root_array_key: String,
+    /// Convert markdown in doc comments (links, backticks, headings, code fences) to plain text
+    pub strip_markdown: bool,
+    /// Leave deprecated fields out instead of annotating them
+    pub omit_deprecated: bool,
+    /// End every field comment with its TOML type, e.g. `type: integer (u32)`
+    pub annotate_types: bool,
+    /// Place single-line doc comments after values (`port = 8080  # Server port`)
+    pub trailing_comments: bool,
+    /// Line up `=` signs within tables and render arrays of short inline tables as columns
+    pub aligned: bool,
+    /// Order of keys within each table, `#[scaffold(order = N)]` on a field takes precedence
+    pub field_order: FieldOrder,
+}
+
+impl Default for ScaffoldOptions {
+    fn default() -> Self {
+        Self {
+            root_array_key: "items".to_string(),
+            strip_markdown: false,
+            omit_deprecated: false,
+            annotate_types: false,
+            trailing_comments: false,
+            aligned: false,
+            field_order: FieldOrder::default(),
+        }
+    }
+}
+
+/// Order of the keys within each table of a scaffold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldOrder {
+    /// Scalar keys in definition order, then `[section]`s and `[[array]]`s
+    #[default]
+    ScalarsFirst,
+    /// Definition order, tables followed by scalar keys are rendered inline or dotted.
+    /// Arrays of tables, and tables holding them, stay sections after the scalar keys
+    Definition,
+    /// Scalar keys, then `[section]`s and `[[array]]`s, each sorted by name
+    Alphabetical,
+}
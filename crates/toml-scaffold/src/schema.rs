@@ -1,49 +1,172 @@
 use schemars::Schema;
 use serde_json::Value;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 use crate::field_path::FieldPath;
+use crate::markdown;
+use crate::options::FieldOrder;
 
 /// Schema information extracted from a JSON schema.
+#[derive(Debug, Clone, Default)]
 pub struct SchemaInfo {
     /// Doc comments for fields
     pub comments: HashMap<FieldPath, String>,
     /// All field paths in the schema
     pub all_fields: HashSet<FieldPath>,
-    /// Optional field paths
-    pub optional_fields: HashSet<FieldPath>,
+    /// All field paths in definition order, each parent before its children
+    pub ordered_fields: Vec<FieldPath>,
     /// Format preferences for fields
     pub formats: HashMap<FieldPath, String>,
+    /// JSON schema type names for fields (e.g. `integer`, `string`, `null`)
+    pub types: HashMap<FieldPath, Vec<String>>,
+    /// Field paths left out of scaffolds
+    pub hidden: HashSet<FieldPath>,
+    /// Field paths rendered commented out
+    pub commented: HashSet<FieldPath>,
+    /// Field paths whose keys, including those of nested sections, get trailing comments
+    pub trailing_comments: HashSet<FieldPath>,
+    /// Field paths whose tables, including nested sections, have aligned `=` signs
+    pub aligned: HashSet<FieldPath>,
+    /// Groups of fields, keys of different groups are separated by an empty line and a header
+    pub groups: HashMap<FieldPath, String>,
+    /// Priorities from `#[scaffold(order = N)]`, lower values come first and the default is 0
+    pub orders: HashMap<FieldPath, i64>,
+    /// Order of keys within each table
+    pub field_order: FieldOrder,
+    /// Convert markdown in comments to plain text when they are rendered
+    pub strip_markdown: bool,
+    /// Deprecated field paths with an optional note, e.g. what to use instead
+    pub deprecated: HashMap<FieldPath, Option<String>>,
+    /// Example values for placeholders, from `examples`, `default`, enum values or the type
+    pub examples: HashMap<FieldPath, toml::Value>,
+    /// TOML type descriptions for fields, e.g. `integer (u32)` or `array of strings`
+    pub type_annotations: HashMap<FieldPath, String>,
 }
 
 /// Extracts comments and field information from schema root.
 pub fn extract_schema_info(schema: &Schema, prefix: &FieldPath) -> SchemaInfo {
-    let mut info = SchemaInfo {
-        comments: HashMap::new(),
-        all_fields: HashSet::new(),
-        optional_fields: HashSet::new(),
-        formats: HashMap::new(),
-    };
+    let mut info = SchemaInfo::default();
 
     let Some(obj) = schema.as_object() else {
         return info;
     };
 
-    // Extract root description and store at empty field path
-    if let Some(desc) = obj.get("description").and_then(|v| v.as_str()) {
-        info.comments.insert(FieldPath::new(), desc.to_string());
-    }
-
     let definitions = obj
         .get("$defs")
         .and_then(|v| v.as_object())
         .cloned()
         .unwrap_or_default();
 
-    process_properties(obj, prefix, &mut info, &definitions);
+    // Extract root description and store at empty field path
+    if let Some(desc) = root_description(schema.as_value(), &definitions) {
+        info.comments.insert(FieldPath::new(), desc.to_string());
+    }
+
+    // Roots may be references (newtypes), subschemas (`Option`) or arrays (`Vec`)
+    extract_nested_schema_info(schema.as_value(), prefix, &mut info, &definitions);
     info
 }
 
+impl SchemaInfo {
+    /// The comment of a field as rendered, converted to plain text with `strip_markdown`
+    pub(crate) fn comment(&self, path: &FieldPath) -> Option<Cow<'_, str>> {
+        let comment = self.comments.get(path)?;
+        Some(if self.strip_markdown {
+            Cow::Owned(markdown::to_plain_text(comment))
+        } else {
+            Cow::Borrowed(comment)
+        })
+    }
+
+    /// Add the metadata of other fields, e.g. the entries of a map nested with `nest_under`
+    pub(crate) fn extend(&mut self, other: SchemaInfo) {
+        self.comments.extend(other.comments);
+        self.all_fields.extend(other.all_fields);
+        self.ordered_fields.extend(other.ordered_fields);
+        self.formats.extend(other.formats);
+        self.types.extend(other.types);
+        self.hidden.extend(other.hidden);
+        self.commented.extend(other.commented);
+        self.trailing_comments.extend(other.trailing_comments);
+        self.aligned.extend(other.aligned);
+        self.groups.extend(other.groups);
+        self.orders.extend(other.orders);
+        self.deprecated.extend(other.deprecated);
+        self.examples.extend(other.examples);
+        self.type_annotations.extend(other.type_annotations);
+    }
+
+    /// Move every path under `prefix`, used when the root value is nested under a key
+    pub(crate) fn nest_under(self, prefix: &FieldPath) -> SchemaInfo {
+        let nest = |path: FieldPath| {
+            let mut nested = prefix.clone();
+            path.segments().iter().for_each(|s| nested.push(s.clone()));
+            nested
+        };
+        SchemaInfo {
+            comments: self
+                .comments
+                .into_iter()
+                .map(|(k, v)| (nest(k), v))
+                .collect(),
+            all_fields: self.all_fields.into_iter().map(nest).collect(),
+            ordered_fields: self.ordered_fields.into_iter().map(nest).collect(),
+            formats: self
+                .formats
+                .into_iter()
+                .map(|(k, v)| (nest(k), v))
+                .collect(),
+            types: self.types.into_iter().map(|(k, v)| (nest(k), v)).collect(),
+            hidden: self.hidden.into_iter().map(nest).collect(),
+            commented: self.commented.into_iter().map(nest).collect(),
+            trailing_comments: self.trailing_comments.into_iter().map(nest).collect(),
+            aligned: self.aligned.into_iter().map(nest).collect(),
+            groups: self.groups.into_iter().map(|(k, v)| (nest(k), v)).collect(),
+            orders: self.orders.into_iter().map(|(k, v)| (nest(k), v)).collect(),
+            field_order: self.field_order,
+            strip_markdown: self.strip_markdown,
+            deprecated: self
+                .deprecated
+                .into_iter()
+                .map(|(k, v)| (nest(k), v))
+                .collect(),
+            examples: self
+                .examples
+                .into_iter()
+                .map(|(k, v)| (nest(k), v))
+                .collect(),
+            type_annotations: self
+                .type_annotations
+                .into_iter()
+                .map(|(k, v)| (nest(k), v))
+                .collect(),
+        }
+    }
+}
+
+/// Find the description of the root, looking through references and array items
+fn root_description<'a>(
+    schema: &'a Value,
+    definitions: &'a serde_json::Map<String, Value>,
+) -> Option<&'a str> {
+    let obj = schema.as_object()?;
+    if let Some(desc) = obj.get("description").and_then(|v| v.as_str()) {
+        return Some(desc);
+    }
+    if let Some(reference) = obj.get("$ref").and_then(|v| v.as_str()) {
+        let ref_name = reference.strip_prefix("#/$defs/").unwrap_or(reference);
+        return root_description(definitions.get(ref_name)?, definitions);
+    }
+    if let Some(items) = obj.get("items") {
+        return root_description(items, definitions);
+    }
+    obj.get("anyOf")
+        .and_then(|v| v.as_array())?
+        .iter()
+        .find_map(|sub_schema| root_description(sub_schema, definitions))
+}
+
 /// Recursively extract comments from nested schema properties
 fn extract_nested_schema_info(
     schema: &Value,
@@ -91,33 +214,290 @@ fn process_properties(
         return;
     };
 
-    let required = obj
-        .get("required")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str())
-                .collect::<HashSet<_>>()
-        })
-        .unwrap_or_default();
-
     for (key, sub_schema) in properties {
         let path = prefix.child(key.clone());
 
-        info.all_fields.insert(path.clone());
-
-        if !required.contains(key.as_str()) {
-            info.optional_fields.insert(path.clone());
+        // Subschemas may list the same property more than once
+        if info.all_fields.insert(path.clone()) {
+            info.ordered_fields.push(path.clone());
         }
 
         if let Some(desc) = sub_schema.get("description").and_then(|v| v.as_str()) {
             info.comments.insert(path.clone(), desc.to_string());
         }
 
+        if sub_schema.get("deprecated").and_then(|v| v.as_bool()) == Some(true) {
+            info.deprecated.insert(path.clone(), None);
+        }
+
+        if let Some(example) = example_value(sub_schema, definitions) {
+            info.examples.insert(path.clone(), example);
+        }
+
+        if let Some(annotation) = type_annotation(sub_schema, definitions) {
+            info.type_annotations.insert(path.clone(), annotation);
+        }
+
+        let mut types = Vec::new();
+        collect_types(sub_schema, definitions, &mut types);
+        if !types.is_empty() {
+            info.types.insert(path.clone(), types);
+        }
+
         extract_nested_schema_info(sub_schema, &path, info, definitions);
     }
 }
 
+/// Pick a value for a placeholder: the first example, the default, the first enum value,
+/// or an empty value of the schema type
+fn example_value(
+    schema: &Value,
+    definitions: &serde_json::Map<String, Value>,
+) -> Option<toml::Value> {
+    let obj = schema.as_object()?;
+
+    let first = |key: &str| obj.get(key).and_then(|v| v.as_array())?.first();
+    let explicit = [
+        first("examples"),
+        obj.get("default"),
+        first("enum"),
+        obj.get("const"),
+    ];
+    if let Some(value) = explicit.into_iter().flatten().find_map(json_to_toml) {
+        return Some(value);
+    }
+
+    if let Some(reference) = obj.get("$ref").and_then(|v| v.as_str()) {
+        let ref_name = reference.strip_prefix("#/$defs/").unwrap_or(reference);
+        if let Some(value) = definitions
+            .get(ref_name)
+            .and_then(|ref_schema| example_value(ref_schema, definitions))
+        {
+            return Some(value);
+        }
+    }
+
+    for key in ["allOf", "anyOf", "oneOf"] {
+        if let Some(subschemas) = obj.get(key).and_then(|v| v.as_array()) {
+            if let Some(value) = subschemas
+                .iter()
+                .find_map(|sub_schema| example_value(sub_schema, definitions))
+            {
+                return Some(value);
+            }
+        }
+    }
+
+    let type_name = match obj.get("type") {
+        Some(Value::String(name)) => Some(name.as_str()),
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(|v| v.as_str())
+            .find(|name| *name != "null"),
+        _ => None,
+    };
+    match type_name? {
+        "string" => Some(toml::Value::String(String::new())),
+        "integer" => Some(toml::Value::Integer(0)),
+        "number" => Some(toml::Value::Float(0.0)),
+        "boolean" => Some(toml::Value::Boolean(false)),
+        "array" => Some(toml::Value::Array(Vec::new())),
+        "object" => object_example(obj, definitions).map(toml::Value::Table),
+        _ => None,
+    }
+}
+
+/// Build a table from the examples of an object's required properties, in definition order
+fn object_example(
+    obj: &serde_json::Map<String, Value>,
+    definitions: &serde_json::Map<String, Value>,
+) -> Option<toml::Table> {
+    let Some(properties) = obj.get("properties").and_then(|v| v.as_object()) else {
+        return Some(toml::Table::new());
+    };
+    let required = obj.get("required").and_then(|v| v.as_array());
+
+    let mut table = toml::Table::new();
+    for (key, sub_schema) in properties {
+        let is_required = required.is_some_and(|r| r.iter().any(|v| v.as_str() == Some(key)));
+        if is_required {
+            table.insert(key.clone(), example_value(sub_schema, definitions)?);
+        }
+    }
+    Some(table)
+}
+
+/// Convert a JSON value to TOML, which has no `null`
+fn json_to_toml(value: &Value) -> Option<toml::Value> {
+    toml::Value::try_from(value).ok()
+}
+
+/// Describe the TOML type of a schema, e.g. `integer (u32)`, `array of strings` or
+/// `table<string, Backend>`. Variants of `anyOf`/`oneOf` are joined with `or`, `null` is skipped
+fn type_annotation(schema: &Value, definitions: &serde_json::Map<String, Value>) -> Option<String> {
+    let mut variants = Vec::new();
+    collect_type_annotations(schema, definitions, &mut variants);
+    (!variants.is_empty()).then(|| variants.join(" or "))
+}
+
+fn collect_type_annotations(
+    schema: &Value,
+    definitions: &serde_json::Map<String, Value>,
+    variants: &mut Vec<String>,
+) {
+    let Some(obj) = schema.as_object() else {
+        return;
+    };
+
+    let push = |variants: &mut Vec<String>, annotation: String| {
+        if !variants.contains(&annotation) {
+            variants.push(annotation);
+        }
+    };
+
+    if let Some(reference) = obj.get("$ref").and_then(|v| v.as_str()) {
+        let ref_name = reference.strip_prefix("#/$defs/").unwrap_or(reference);
+        if let Some(ref_schema) = definitions.get(ref_name) {
+            if is_table_schema(ref_schema) {
+                push(variants, format!("table ({})", ref_name));
+            } else {
+                collect_type_annotations(ref_schema, definitions, variants);
+            }
+        }
+    }
+
+    for key in ["allOf", "anyOf", "oneOf"] {
+        if let Some(subschemas) = obj.get(key).and_then(|v| v.as_array()) {
+            for sub_schema in subschemas {
+                collect_type_annotations(sub_schema, definitions, variants);
+            }
+        }
+    }
+
+    let type_names: Vec<&str> = match obj.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(|v| v.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    for type_name in type_names {
+        let format = obj
+            .get("format")
+            .and_then(|v| v.as_str())
+            .map(|f| format!(" ({})", rust_type_of_format(f)))
+            .unwrap_or_default();
+        let annotation = match type_name {
+            "string" => format!("string{}", format),
+            "integer" => format!("integer{}", format),
+            "number" => format!("float{}", format),
+            "boolean" => "boolean".to_string(),
+            "array" => match obj.get("items").filter(|items| items.is_object()) {
+                Some(items) => match type_annotation(items, definitions) {
+                    Some(item) => format!("array of {}", plural(&item)),
+                    None => "array".to_string(),
+                },
+                None => "array".to_string(),
+            },
+            "object" => match obj
+                .get("additionalProperties")
+                .filter(|values| values.is_object())
+            {
+                Some(values) => format!(
+                    "table<string, {}>",
+                    map_value_name(values, definitions).unwrap_or_else(|| "any".to_string())
+                ),
+                None => "table".to_string(),
+            },
+            _ => continue,
+        };
+        push(variants, annotation);
+    }
+}
+
+/// Whether a definition is rendered as a table, so references to it are named
+fn is_table_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(|v| v.as_str()) == Some("object")
+        || schema.get("properties").is_some()
+}
+
+/// Name of the values of a map, the definition name for structs
+fn map_value_name(schema: &Value, definitions: &serde_json::Map<String, Value>) -> Option<String> {
+    let reference = schema.get("$ref").and_then(|v| v.as_str());
+    if let Some(ref_name) = reference.map(|r| r.strip_prefix("#/$defs/").unwrap_or(r)) {
+        if definitions.get(ref_name).is_some_and(is_table_schema) {
+            return Some(ref_name.to_string());
+        }
+    }
+    type_annotation(schema, definitions)
+}
+
+/// Map a JSON schema `format` to the Rust type schemars derived it from, e.g. `uint32` to `u32`
+fn rust_type_of_format(format: &str) -> String {
+    let bits = |rest: &str| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit());
+    match format {
+        "float" => "f32".to_string(),
+        "double" => "f64".to_string(),
+        "int" => "isize".to_string(),
+        "uint" => "usize".to_string(),
+        _ => match (format.strip_prefix("uint"), format.strip_prefix("int")) {
+            (Some(rest), _) if bits(rest) => format!("u{}", rest),
+            (_, Some(rest)) if bits(rest) => format!("i{}", rest),
+            _ => format.to_string(),
+        },
+    }
+}
+
+/// Pluralize the leading type word of every variant, `integer (u8)` becomes `integers (u8)`
+fn plural(annotation: &str) -> String {
+    annotation
+        .split(" or ")
+        .map(|variant| match variant.split_once([' ', '<']) {
+            Some((word, rest)) => {
+                let separator = &variant[word.len()..word.len() + 1];
+                format!("{}s{}{}", word, separator, rest)
+            }
+            None => format!("{}s", variant),
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// Collect JSON schema type names, following references and subschemas
+fn collect_types(
+    schema: &Value,
+    definitions: &serde_json::Map<String, Value>,
+    types: &mut Vec<String>,
+) {
+    let Some(obj) = schema.as_object() else {
+        return;
+    };
+
+    let mut push = |name: &str| {
+        if !types.iter().any(|t| t == name) {
+            types.push(name.to_string());
+        }
+    };
+    match obj.get("type") {
+        Some(Value::String(name)) => push(name),
+        Some(Value::Array(names)) => names.iter().filter_map(|v| v.as_str()).for_each(push),
+        _ => {}
+    }
+
+    if let Some(reference) = obj.get("$ref").and_then(|v| v.as_str()) {
+        let ref_name = reference.strip_prefix("#/$defs/").unwrap_or(reference);
+        if let Some(ref_schema) = definitions.get(ref_name) {
+            collect_types(ref_schema, definitions, types);
+        }
+    }
+
+    for key in ["allOf", "anyOf", "oneOf"] {
+        if let Some(subschemas) = obj.get(key).and_then(|v| v.as_array()) {
+            for sub_schema in subschemas {
+                collect_types(sub_schema, definitions, types);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,10 +524,13 @@ mod tests {
         assert!(info
             .all_fields
             .contains(&FieldPath::from_vec(vec!["optional".to_string()])));
-        assert_eq!(info.optional_fields.len(), 1);
-        assert!(info
-            .optional_fields
-            .contains(&FieldPath::from_vec(vec!["optional".to_string()])));
+        assert_eq!(
+            info.ordered_fields,
+            vec![
+                FieldPath::from_vec(vec!["required".to_string()]),
+                FieldPath::from_vec(vec!["optional".to_string()]),
+            ]
+        );
     }
 
     #[test]
@@ -201,13 +584,139 @@ mod tests {
         );
     }
 
+    #[derive(Serialize, JsonSchema)]
+    struct Typed {
+        count: Option<u32>,
+        inner: Option<Inner>,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_example_values() {
+        #[derive(Serialize, JsonSchema)]
+        #[allow(dead_code)]
+        enum Mode {
+            Fast,
+            Safe,
+        }
+
+        #[derive(Serialize, JsonSchema)]
+        #[allow(dead_code)]
+        struct Examples {
+            port: Option<u16>,
+            ratio: f64,
+            tags: Vec<String>,
+            mode: Option<Mode>,
+            #[schemars(example = "example.com")]
+            host: String,
+            #[serde(default = "default_level")]
+            level: String,
+            any: serde_json::Value,
+            nested: Option<Nested>,
+        }
+
+        #[derive(Serialize, JsonSchema)]
+        #[allow(dead_code)]
+        struct Nested {
+            size: u32,
+            name: String,
+            label: Option<String>,
+        }
+
+        fn default_level() -> String {
+            "info".to_string()
+        }
+
+        let info = extract_schema_info(&schemars::schema_for!(Examples), &FieldPath::new());
+        let example = |key: &str| {
+            info.examples
+                .get(&FieldPath::from_vec(vec![key.to_string()]))
+        };
+        assert_eq!(example("port"), Some(&toml::Value::Integer(0)));
+        assert_eq!(example("ratio"), Some(&toml::Value::Float(0.0)));
+        assert_eq!(example("tags"), Some(&toml::Value::Array(Vec::new())));
+        assert_eq!(example("mode"), Some(&toml::Value::from("Fast")));
+        assert_eq!(example("host"), Some(&toml::Value::from("example.com")));
+        assert_eq!(example("level"), Some(&toml::Value::from("info")));
+        assert_eq!(example("any"), None);
+        assert_eq!(
+            example("nested").map(|v| v.to_string()),
+            Some("{ size = 0, name = \"\" }".to_string())
+        );
+    }
+
+    #[test]
+    fn test_type_annotations() {
+        #[derive(Serialize, JsonSchema)]
+        #[allow(dead_code)]
+        enum Mode {
+            Fast,
+            Safe,
+        }
+
+        #[derive(Serialize, JsonSchema)]
+        #[allow(dead_code)]
+        struct Annotated {
+            port: Option<u16>,
+            ratio: f64,
+            name: String,
+            enabled: bool,
+            tags: Vec<String>,
+            sizes: Vec<u64>,
+            mode: Mode,
+            inner: Option<Inner>,
+            inners: Vec<Inner>,
+            backends: HashMap<String, Inner>,
+            limits: HashMap<String, u32>,
+            grid: Vec<Vec<i8>>,
+            any: serde_json::Value,
+        }
+
+        let info = extract_schema_info(&schemars::schema_for!(Annotated), &FieldPath::new());
+        let annotation = |key: &str| {
+            info.type_annotations
+                .get(&FieldPath::from_vec(vec![key.to_string()]))
+                .map(String::as_str)
+        };
+        assert_eq!(annotation("port"), Some("integer (u16)"));
+        assert_eq!(annotation("ratio"), Some("float (f64)"));
+        assert_eq!(annotation("name"), Some("string"));
+        assert_eq!(annotation("enabled"), Some("boolean"));
+        assert_eq!(annotation("tags"), Some("array of strings"));
+        assert_eq!(annotation("sizes"), Some("array of integers (u64)"));
+        assert_eq!(annotation("mode"), Some("string"));
+        assert_eq!(annotation("inner"), Some("table (Inner)"));
+        assert_eq!(annotation("inners"), Some("array of tables (Inner)"));
+        assert_eq!(annotation("backends"), Some("table<string, Inner>"));
+        assert_eq!(annotation("limits"), Some("table<string, integer (u32)>"));
+        assert_eq!(annotation("grid"), Some("array of arrays of integers (i8)"));
+        assert_eq!(annotation("any"), None);
+    }
+
+    #[test]
+    fn test_extract_types() {
+        let schema = schemars::schema_for!(Typed);
+        let info = extract_schema_info(&schema, &FieldPath::new());
+
+        let types = |key: &str| info.types.get(&FieldPath::from_vec(vec![key.to_string()]));
+        assert_eq!(
+            types("count"),
+            Some(&vec!["integer".to_string(), "null".to_string()])
+        );
+        assert_eq!(
+            types("inner"),
+            Some(&vec!["object".to_string(), "null".to_string()])
+        );
+        assert_eq!(types("tags"), Some(&vec!["array".to_string()]));
+    }
+
     #[test]
     fn test_empty_schema() {
         let schema = serde_json::from_value(serde_json::json!({})).unwrap();
         let info = extract_schema_info(&schema, &FieldPath::new());
 
         assert_eq!(info.all_fields.len(), 0);
-        assert_eq!(info.optional_fields.len(), 0);
+        assert_eq!(info.ordered_fields.len(), 0);
         assert_eq!(info.comments.len(), 0);
     }
 
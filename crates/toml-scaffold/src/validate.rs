@@ -0,0 +1,99 @@
+use crate::field_path::FieldPath;
+use crate::schema::SchemaInfo;
+use crate::TomlScaffold;
+
+/// A problem found in a config file that does not prevent loading it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// A deprecated field is set
+    Deprecated {
+        /// Path of the field
+        path: FieldPath,
+        /// Deprecation note, e.g. what to use instead
+        note: Option<String>,
+    },
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::Deprecated { path, note: None } => write!(f, "`{}` is deprecated", path),
+            Warning::Deprecated {
+                path,
+                note: Some(note),
+            } => write!(f, "`{}` is deprecated: {}", path, note),
+        }
+    }
+}
+
+/// Checks a TOML config of type `T` for deprecated fields, in document order.
+pub fn validate<T: TomlScaffold>(config: &str) -> Result<Vec<Warning>, toml::de::Error> {
+    let table: toml::Table = toml::from_str(config)?;
+    Ok(warnings(&table, &T::schema_info()))
+}
+
+/// Collect warnings for a parsed config
+pub(crate) fn warnings(table: &toml::Table, info: &SchemaInfo) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    collect_deprecated(table, &FieldPath::new(), info, &mut warnings);
+    warnings
+}
+
+/// Walk tables and arrays of tables, reporting each deprecated path once
+fn collect_deprecated(
+    table: &toml::Table,
+    path: &FieldPath,
+    info: &SchemaInfo,
+    warnings: &mut Vec<Warning>,
+) {
+    for (key, value) in table {
+        let current_path = path.child(key.clone());
+        if let Some(note) = info.deprecated.get(&current_path) {
+            let seen = warnings
+                .iter()
+                .any(|Warning::Deprecated { path, .. }| *path == current_path);
+            if !seen {
+                warnings.push(Warning::Deprecated {
+                    path: current_path.clone(),
+                    note: note.clone(),
+                });
+            }
+        }
+
+        match value {
+            toml::Value::Table(nested) => collect_deprecated(nested, &current_path, info, warnings),
+            toml::Value::Array(items) => {
+                for nested in items.iter().filter_map(|item| item.as_table()) {
+                    collect_deprecated(nested, &current_path, info, warnings);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deprecated_warnings() {
+        let mut info = SchemaInfo::default();
+        let path = |s: &str| s.parse::<FieldPath>().unwrap();
+        info.deprecated.insert(path("host"), None);
+        info.deprecated
+            .insert(path("servers.port"), Some("use addr".to_string()));
+
+        let table =
+            toml::from_str("name = 1\nhost = 2\n[[servers]]\nport = 1\n[[servers]]\nport = 2")
+                .unwrap();
+        let warnings = warnings(&table, &info);
+        assert_eq!(
+            warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            [
+                "`host` is deprecated",
+                "`servers.port` is deprecated: use addr"
+            ]
+        );
+    }
+}
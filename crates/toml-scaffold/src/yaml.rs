@@ -0,0 +1,200 @@
+use crate::field_path::FieldPath;
+use crate::format::{append_prefixed_comment, missing_fields};
+use crate::schema::SchemaInfo;
+
+/// Format TOML value as YAML with comments at the appropriate paths
+pub fn format_with_comments(
+    value: &toml::Value,
+    info: &SchemaInfo,
+    path: &FieldPath,
+    indent: usize,
+) -> String {
+    let toml::Value::Table(table) = value else {
+        unreachable!()
+    };
+    let pad = " ".repeat(indent);
+    let mut result = String::new();
+
+    // Append root description if at root level
+    if path.is_empty() && info.comments.contains_key(&FieldPath::new()) {
+        append_prefixed_comment(&mut result, info, &FieldPath::new(), "", "#");
+        result.push('\n');
+    }
+
+    // Keys keep their definition order, YAML has no scalars-first constraint. Rule 17:
+    // missing fields are shown as comments where they are defined
+    let position = |field: &FieldPath| info.ordered_fields.iter().position(|f| f == field);
+    let mut missing = missing_fields(info, table, path).into_iter().peekable();
+    for (key, val) in table {
+        let current_path = path.child(key.clone());
+        if let Some(index) = position(&current_path) {
+            while let Some(field) = missing.next_if(|field| position(field) < Some(index)) {
+                append_placeholder(&mut result, info, field, &pad);
+            }
+        }
+        let format_pref = info.formats.get(&current_path).map(|s| s.as_str());
+
+        append_prefixed_comment(&mut result, info, &current_path, &pad, "#");
+        result.push_str(&format!("{}{}:", pad, format_key(key)));
+
+        match val {
+            toml::Value::Table(nested) if !nested.is_empty() && format_pref != Some("inline") => {
+                result.push('\n');
+                result.push_str(&format_with_comments(val, info, &current_path, indent + 2));
+            }
+            toml::Value::Array(arr)
+                if !arr.is_empty() && arr.iter().all(|v| matches!(v, toml::Value::Table(_))) =>
+            {
+                // Array of tables: one block mapping per sequence entry
+                result.push('\n');
+                for item in arr {
+                    let body = format_with_comments(item, info, &current_path, indent + 2);
+                    if body.is_empty() {
+                        result.push_str(&format!("{}- {{}}\n", pad));
+                    } else {
+                        result.push_str(&format!("{}- {}", pad, &body[indent + 2..]));
+                    }
+                }
+            }
+            toml::Value::Array(arr) if !arr.is_empty() && format_pref == Some("multiline") => {
+                result.push('\n');
+                for item in arr {
+                    result.push_str(&format!("{}- {}\n", pad, format_value(item)));
+                }
+            }
+            _ => result.push_str(&format!(" {}\n", format_value(val))),
+        }
+    }
+
+    for field in missing {
+        append_placeholder(&mut result, info, field, &pad);
+    }
+
+    result
+}
+
+/// Rule 17: Append a missing field as a commented out key with an example value
+fn append_placeholder(result: &mut String, info: &SchemaInfo, field: &FieldPath, pad: &str) {
+    let key = field.segments().last().unwrap();
+    append_prefixed_comment(result, info, field, pad, "#");
+    let example = info
+        .examples
+        .get(field)
+        .map_or("...".to_string(), format_value);
+    result.push_str(&format!("{}# {}: {}\n", pad, format_key(key), example));
+}
+
+/// Convert TOML value to a YAML flow representation
+fn format_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => quote(s),
+        toml::Value::Integer(i) => i.to_string(),
+        toml::Value::Float(f) if f.is_nan() => ".nan".to_string(),
+        toml::Value::Float(f) if f.is_infinite() && *f > 0.0 => ".inf".to_string(),
+        toml::Value::Float(f) if f.is_infinite() => "-.inf".to_string(),
+        toml::Value::Float(f) => {
+            // Reuse TOML float formatting so whole numbers keep their `.0`
+            use toml_writer::TomlWrite;
+            let mut result = String::new();
+            let _ = result.value(f);
+            result
+        }
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Datetime(dt) => quote(&dt.to_string()),
+        toml::Value::Array(arr) => {
+            let items: Vec<String> = arr.iter().map(format_value).collect();
+            format!("[{}]", items.join(", "))
+        }
+        toml::Value::Table(table) => {
+            if table.is_empty() {
+                return "{}".to_string();
+            }
+            let items: Vec<String> = table
+                .iter()
+                .map(|(k, v)| format!("{}: {}", format_key(k), format_value(v)))
+                .collect();
+            format!("{{ {} }}", items.join(", "))
+        }
+    }
+}
+
+/// Format a mapping key, quoting it unless it is unambiguous as a plain scalar
+fn format_key(key: &str) -> String {
+    let is_plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !is_reserved_word(key);
+    if is_plain {
+        key.to_string()
+    } else {
+        quote(key)
+    }
+}
+
+/// Check if a plain scalar would be resolved as a boolean or null by YAML parsers
+fn is_reserved_word(s: &str) -> bool {
+    matches!(
+        s.to_ascii_lowercase().as_str(),
+        "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n" | "null"
+    )
+}
+
+/// Double-quoted YAML scalar (JSON string escapes are valid YAML escapes)
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_key_plain() {
+        assert_eq!(format_key("host"), "host");
+        assert_eq!(format_key("max_files-per_task"), "max_files-per_task");
+    }
+
+    #[test]
+    fn test_format_key_quoted() {
+        assert_eq!(format_key("field.with.dots"), "\"field.with.dots\"");
+        assert_eq!(format_key("with space"), "\"with space\"");
+        assert_eq!(format_key("123"), "\"123\"");
+        assert_eq!(format_key(""), "\"\"");
+        assert_eq!(format_key("yes"), "\"yes\"");
+        assert_eq!(format_key("Null"), "\"Null\"");
+    }
+
+    #[test]
+    fn test_format_value_scalars() {
+        assert_eq!(
+            format_value(&toml::Value::String("a\"b".into())),
+            "\"a\\\"b\""
+        );
+        assert_eq!(
+            format_value(&toml::Value::String("l1\nl2".into())),
+            "\"l1\\nl2\""
+        );
+        assert_eq!(format_value(&toml::Value::Integer(42)), "42");
+        assert_eq!(format_value(&toml::Value::Float(1.0)), "1.0");
+        assert_eq!(
+            format_value(&toml::Value::Float(f64::NEG_INFINITY)),
+            "-.inf"
+        );
+        assert_eq!(format_value(&toml::Value::Boolean(true)), "true");
+    }
+
+    #[test]
+    fn test_format_value_collections() {
+        let arr = toml::Value::Array(vec![toml::Value::Integer(1), toml::Value::Integer(2)]);
+        assert_eq!(format_value(&arr), "[1, 2]");
+
+        let mut table = toml::map::Map::new();
+        assert_eq!(format_value(&toml::Value::Table(table.clone())), "{}");
+        table.insert("key".to_string(), toml::Value::Integer(1));
+        assert_eq!(format_value(&toml::Value::Table(table)), "{ key: 1 }");
+    }
+}
@@ -0,0 +1,160 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{ScaffoldOptions, TomlScaffold};
+
+/// Router configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Worker threads
+    workers: u32,
+    /// Request limits
+    #[format = "dotted"]
+    limits: Limits,
+    /// Route table
+    routes: Vec<Route>,
+    /// Upstream servers
+    upstreams: Vec<Upstream>,
+    /// Server settings
+    #[scaffold(aligned)]
+    server: Server,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Limits {
+    body_size: u32,
+    rps: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Route {
+    path: String,
+    target: String,
+    weight: u8,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Upstream {
+    /// Upstream address
+    addr: String,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Listen host
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Connection timeouts
+    #[format = "*dotted"]
+    timeouts: Timeouts,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Timeouts {
+    read: u32,
+    idle_keepalive: u32,
+    tls: TlsTimeouts,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct TlsTimeouts {
+    handshake: u32,
+}
+
+fn config() -> Config {
+    Config {
+        name: "router".to_string(),
+        workers: 4,
+        limits: Limits {
+            body_size: 1024,
+            rps: 100,
+        },
+        routes: vec![
+            Route {
+                path: "/".to_string(),
+                target: "web".to_string(),
+                weight: 1,
+            },
+            Route {
+                path: "/api".to_string(),
+                target: "api-backend".to_string(),
+                weight: 10,
+            },
+        ],
+        upstreams: vec![Upstream {
+            addr: "10.0.0.2:80".to_string(),
+        }],
+        server: Server {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            timeouts: Timeouts {
+                read: 30,
+                idle_keepalive: 60,
+                tls: TlsTimeouts { handshake: 5 },
+            },
+        },
+    }
+}
+
+#[test]
+fn test_aligned_option() {
+    let options = ScaffoldOptions {
+        aligned: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("aligned_keys.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_aligned_attribute() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("aligned_keys_section.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+/// Cache settings
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Cache {
+    /// Cache directory
+    dir: String,
+    /// Entry lifetime in seconds
+    #[scaffold(commented, trailing_comments)]
+    #[serde(default)]
+    ttl: u32,
+    /// Maximum size
+    #[scaffold(trailing_comments)]
+    size: u32,
+    /// Eviction policy
+    #[scaffold(commented)]
+    #[serde(default)]
+    eviction_policy: String,
+}
+
+#[test]
+fn test_aligned_commented_fields() {
+    let cache = Cache {
+        dir: "/tmp/cache".to_string(),
+        ttl: 60,
+        size: 512,
+        eviction_policy: "lru".to_string(),
+    };
+    let options = ScaffoldOptions {
+        aligned: true,
+        ..Default::default()
+    };
+    let scaffold = cache.to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("aligned_keys_commented.toml"));
+
+    // Commented fields take their defaults
+    let deserialized: Cache = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized.ttl, 0);
+    assert_eq!(deserialized.size, 512);
+}
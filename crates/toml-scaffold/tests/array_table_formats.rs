@@ -0,0 +1,96 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::TomlScaffold;
+
+/// Proxy configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Route table
+    #[format = "inline"]
+    routes: Vec<Route>,
+    /// Upstream backends
+    backends: Vec<Backend>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Route {
+    path: String,
+    target: String,
+    #[format = "dotted"]
+    timeouts: Timeouts,
+    #[format = "multiline"]
+    methods: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Timeouts {
+    connect: u32,
+    read: u32,
+}
+
+fn route(path: &str, target: &str) -> Route {
+    Route {
+        path: path.to_string(),
+        target: target.to_string(),
+        timeouts: Timeouts {
+            connect: 1,
+            read: 30,
+        },
+        methods: vec!["GET".to_string(), "POST".to_string()],
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Backend {
+    /// Backend address
+    addr: String,
+    /// Connection pool
+    #[format = "inline"]
+    pool: Pool,
+    /// Request limits
+    #[format = "dotted"]
+    limits: Limits,
+    /// Health check paths
+    #[format = "multiline"]
+    checks: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Pool {
+    min: u32,
+    max: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Limits {
+    rps: u32,
+    burst: u32,
+}
+
+fn backend(addr: &str) -> Backend {
+    Backend {
+        addr: addr.to_string(),
+        pool: Pool { min: 1, max: 8 },
+        limits: Limits {
+            rps: 100,
+            burst: 20,
+        },
+        checks: vec!["/health".to_string(), "/ready".to_string()],
+    }
+}
+
+fn config() -> Config {
+    Config {
+        routes: vec![route("/", "web"), route("/api", "api")],
+        backends: vec![backend("10.0.0.2:80"), backend("10.0.0.3:80")],
+    }
+}
+
+#[test]
+fn test_array_table_formats() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("array_table_formats.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
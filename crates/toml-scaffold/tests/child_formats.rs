@@ -0,0 +1,127 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::TomlScaffold;
+
+/// Cluster configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Node settings, children as dotted keys
+    #[format = "*dotted"]
+    node: Node,
+    /// Storage settings, children flattened at any depth
+    #[format = "*dotted-nested"]
+    storage: Storage,
+    /// Pool settings, children as inline tables
+    #[format = "*inline"]
+    pools: Pools,
+    /// Peer lists, children as multiline arrays
+    #[format = "*multiline"]
+    peers: Peers,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Node {
+    /// Node name
+    name: String,
+    /// Optional node label
+    label: Option<String>,
+    /// Network settings
+    network: Network,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Network {
+    /// Listen port
+    port: u16,
+    /// Optional public address
+    public_addr: Option<String>,
+    /// TLS settings
+    tls: Tls,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Tls {
+    enabled: bool,
+    /// Certificate path
+    cert: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Storage {
+    /// Data directory
+    path: String,
+    /// Cache settings
+    cache: Cache,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Cache {
+    /// Cache size in MiB
+    size: u32,
+    /// Eviction settings
+    eviction: Tls,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Pools {
+    read: Pool,
+    write: Pool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Pool {
+    min: u32,
+    max: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Peers {
+    /// Seed nodes
+    seeds: Vec<String>,
+    /// Witness nodes
+    witnesses: Vec<String>,
+}
+
+fn config() -> Config {
+    Config {
+        node: Node {
+            name: "node-1".to_string(),
+            label: None,
+            network: Network {
+                port: 7000,
+                public_addr: None,
+                tls: Tls {
+                    enabled: true,
+                    cert: None,
+                },
+            },
+        },
+        storage: Storage {
+            path: "/var/lib/cluster".to_string(),
+            cache: Cache {
+                size: 64,
+                eviction: Tls {
+                    enabled: false,
+                    cert: None,
+                },
+            },
+        },
+        pools: Pools {
+            read: Pool { min: 1, max: 8 },
+            write: Pool { min: 1, max: 2 },
+        },
+        peers: Peers {
+            seeds: vec!["10.0.0.2:7000".to_string(), "10.0.0.3:7000".to_string()],
+            witnesses: vec!["10.0.0.4:7000".to_string()],
+        },
+    }
+}
+
+#[test]
+fn test_child_formats() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("child_formats.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
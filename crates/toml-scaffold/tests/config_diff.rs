@@ -0,0 +1,55 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use toml_scaffold::{diff, Change, TomlScaffold};
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Enable debug output
+    debug: Option<bool>,
+    /// Server configuration
+    server: Server,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Server {
+    /// Bind address
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Optional display name
+    label: Option<String>,
+    /// TLS settings
+    tls: Option<Tls>,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Tls {
+    /// Certificate path
+    cert: String,
+}
+
+#[test]
+fn test_config_diff() {
+    let result = diff::<Config>(
+        include_str!("config_diff_old.toml"),
+        include_str!("config_diff_new.toml"),
+    )
+    .unwrap();
+
+    let paths: Vec<String> = result.entries.iter().map(|e| e.path.to_string()).collect();
+    assert_eq!(
+        paths,
+        ["debug", "server.port", "server.label", "server.tls.cert"]
+    );
+    assert_eq!(result.entries[0].change, Change::Removed(false.into()));
+    assert_eq!(result.entries[1].comment.as_deref(), Some("Listen port"));
+    assert_eq!(result.render(), include_str!("config_diff.txt"));
+}
+
+#[test]
+fn test_config_diff_identical() {
+    let config = include_str!("config_diff_old.toml");
+    assert!(diff::<Config>(config, config).unwrap().is_empty());
+}
@@ -0,0 +1,59 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::TomlScaffold;
+
+/// Application configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Primary database
+    primary: Database,
+    /// Optional replica database
+    replica: Option<Database>,
+    /// Listener address
+    listen: Address,
+    /// Analytics database, rendered as a section
+    #[format = "standard"]
+    analytics: Database,
+    /// Read-only databases
+    mirrors: Vec<Database>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[toml_scaffold(format = "inline")]
+struct Database {
+    host: String,
+    port: u16,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[toml_scaffold(format = "dotted")]
+struct Address {
+    host: String,
+    port: u16,
+}
+
+fn database(host: &str) -> Database {
+    Database {
+        host: host.to_string(),
+        port: 5432,
+    }
+}
+
+#[test]
+fn test_container_format() {
+    let config = Config {
+        primary: database("db-1"),
+        replica: Some(database("db-2")),
+        listen: Address {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+        },
+        analytics: database("db-3"),
+        mirrors: vec![database("db-4"), database("db-5")],
+    };
+    let scaffold = config.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("container_format.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config);
+}
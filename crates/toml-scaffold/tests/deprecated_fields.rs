@@ -0,0 +1,95 @@
+#![allow(deprecated)]
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{validate, FieldPath, Loader, ScaffoldOptions, TomlScaffold, Warning};
+
+/// Proxy configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Upstream server
+    server: Server,
+    /// Request timeout in seconds
+    #[deprecated(note = "use server.timeout instead")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    /// Enable legacy mode
+    #[deprecated]
+    #[serde(default)]
+    legacy: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Upstream address
+    addr: String,
+    /// Upstream host name
+    #[scaffold(deprecated = "use server.addr instead")]
+    #[serde(default)]
+    host: String,
+    /// Request timeout in seconds
+    timeout: u64,
+}
+
+fn config() -> Config {
+    Config {
+        server: Server {
+            addr: "10.0.0.1:80".to_string(),
+            host: String::new(),
+            timeout: 30,
+        },
+        timeout: None,
+        legacy: false,
+    }
+}
+
+#[test]
+fn test_deprecated_annotations() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("deprecated_fields.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_omit_deprecated() {
+    let options = ScaffoldOptions {
+        omit_deprecated: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert!(!scaffold.contains("DEPRECATED"));
+    assert!(!scaffold.contains("legacy"));
+    assert!(!scaffold.contains("host"));
+    assert!(!scaffold.contains("# timeout"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_validate_deprecated() {
+    let user_config = "timeout = 5\n[server]\naddr = \"a\"\nhost = \"b\"\ntimeout = 1\n";
+    let path = |s: &str| s.parse::<FieldPath>().unwrap();
+    let expected = vec![
+        Warning::Deprecated {
+            path: path("timeout"),
+            note: Some("use server.timeout instead".to_string()),
+        },
+        Warning::Deprecated {
+            path: path("server.host"),
+            note: Some("use server.addr instead".to_string()),
+        },
+    ];
+    assert_eq!(validate::<Config>(user_config).unwrap(), expected);
+
+    let loaded = Loader::<Config>::new()
+        .document(user_config)
+        .load()
+        .unwrap();
+    assert_eq!(loaded.warnings, expected);
+    assert!(validate::<Config>("[server]\naddr = \"a\"\ntimeout = 1\n")
+        .unwrap()
+        .is_empty());
+}
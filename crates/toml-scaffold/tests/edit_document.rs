@@ -0,0 +1,81 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{merge_document, rerender, set_value, EditError, FieldPath, TomlScaffold};
+
+/// Server configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Service name
+    name: String,
+    /// Listener settings
+    listen: Listen,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Listen {
+    /// Address to bind
+    host: String,
+    /// Port to bind
+    port: u16,
+}
+
+fn config() -> Config {
+    Config {
+        name: "api".to_string(),
+        listen: Listen {
+            host: "127.0.0.1".to_string(),
+            port: 80,
+        },
+    }
+}
+
+#[test]
+fn test_edit_document() {
+    let config = config();
+    let mut doc = config.to_document().unwrap();
+    assert_eq!(doc.to_string(), config.to_scaffold().unwrap());
+
+    set_value(&mut doc, &"listen.port".parse::<FieldPath>().unwrap(), 8080).unwrap();
+    set_value(&mut doc, &"name".parse::<FieldPath>().unwrap(), "gateway").unwrap();
+    let edited = doc.to_string();
+    assert_eq!(edited, include_str!("edit_document.toml"));
+
+    let parsed: Config = toml::from_str(&edited).unwrap();
+    assert_eq!(parsed.name, "gateway");
+    assert_eq!(parsed.listen.port, 8080);
+}
+
+#[test]
+fn test_merge_document() {
+    let scaffold = config().to_document().unwrap();
+    let mut doc: toml_edit::DocumentMut =
+        "# My service\nname = \"web\" # renamed\n".parse().unwrap();
+    merge_document(&mut doc, &scaffold);
+    assert_eq!(
+        doc.to_string(),
+        "# My service\nname = \"web\" # renamed\n\n# Listener settings\n[listen]\n# Address to bind\nhost = \"127.0.0.1\"\n# Port to bind\nport = 80\n"
+    );
+
+    let parsed: Config = toml::from_str(&doc.to_string()).unwrap();
+    assert_eq!(parsed.name, "web");
+    assert_eq!(parsed.listen.port, 80);
+}
+
+#[test]
+fn test_rerender() {
+    let scaffold = config().to_document().unwrap();
+    let mut doc: toml_edit::DocumentMut =
+        "# My service\nname = \"web\"\n\n[listen]\nport = 9000\n\n[extra]\nkey = 1\n"
+            .parse()
+            .unwrap();
+    rerender(&mut doc, &scaffold, &"listen".parse().unwrap()).unwrap();
+    assert_eq!(
+        doc.to_string(),
+        "# My service\nname = \"web\"\n\n# Listener settings\n[listen]\n# Address to bind\nhost = \"127.0.0.1\"\n# Port to bind\nport = 80\n\n[extra]\nkey = 1\n"
+    );
+
+    assert_eq!(
+        rerender(&mut doc, &scaffold, &"listen.timeout".parse().unwrap()),
+        Err(EditError::NotInScaffold("listen.timeout".parse().unwrap()))
+    );
+}
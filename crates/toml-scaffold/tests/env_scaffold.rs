@@ -0,0 +1,133 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use toml_scaffold::{EnvNaming, Migration, TomlScaffold};
+
+/// Application configuration
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Enabled features
+    features: Vec<String>,
+    /// Optional worker count
+    workers: Option<u32>,
+    /// Server configuration
+    server: Server,
+    /// Database connection, disabled when not set
+    database: Option<Database>,
+    /// Upstream backends
+    backends: Vec<Backend>,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Server {
+    /// Bind address
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Greeting sent to clients
+    banner: String,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Database {
+    /// Connection URL
+    url: String,
+    /// Pool size
+    pool: u32,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Backend {
+    /// Backend address
+    addr: String,
+    /// Relative weight
+    weight: u32,
+}
+
+#[test]
+fn test_env_scaffold() {
+    let config = Config {
+        name: "myapp".to_string(),
+        features: vec!["auth".to_string(), "metrics".to_string()],
+        workers: None,
+        server: Server {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            banner: "Hello there".to_string(),
+        },
+        database: None,
+        backends: vec![
+            Backend {
+                addr: "10.0.0.1".to_string(),
+                weight: 1,
+            },
+            Backend {
+                addr: "10.0.0.2".to_string(),
+                weight: 2,
+            },
+        ],
+    };
+    let scaffold = config
+        .to_env_scaffold(&EnvNaming::new("APP", "__"))
+        .unwrap();
+    assert_eq!(scaffold, include_str!("env_scaffold.env"));
+}
+
+#[test]
+fn test_env_scaffold_name_collision() {
+    #[derive(Serialize, JsonSchema, TomlScaffold)]
+    struct Colliding {
+        #[serde(rename = "log.level")]
+        dotted: String,
+        log_level: String,
+    }
+
+    let config = Colliding {
+        dotted: "info".to_string(),
+        log_level: "debug".to_string(),
+    };
+    let error = config.to_env_scaffold(&EnvNaming::default()).unwrap_err();
+    assert!(
+        error.to_string().contains(
+            "`\"log.level\"` and `log_level` both map to the environment variable `LOG_LEVEL`"
+        ),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_env_scaffold_without_schema_version() {
+    #[derive(Serialize, JsonSchema, TomlScaffold)]
+    #[toml_scaffold(migrations = "migrations")]
+    struct Versioned {
+        /// Listen port
+        port: u16,
+    }
+
+    fn migrations() -> Vec<Migration> {
+        vec![Migration::new(1).rename("listen_port", "port")]
+    }
+
+    let config = Versioned { port: 8080 };
+    let scaffold = config
+        .to_env_scaffold(&EnvNaming::new("APP", "__"))
+        .unwrap();
+    assert_eq!(scaffold, "# Listen port\nAPP__PORT=8080\n");
+    // Only the TOML scaffold tracks the version
+    assert!(config
+        .to_scaffold()
+        .unwrap()
+        .contains("schema_version = 1\n"));
+    #[cfg(feature = "yaml")]
+    assert!(!config
+        .to_yaml_scaffold()
+        .unwrap()
+        .contains("schema_version"));
+    #[cfg(feature = "jsonc")]
+    assert!(!config
+        .to_jsonc_scaffold()
+        .unwrap()
+        .contains("schema_version"));
+}
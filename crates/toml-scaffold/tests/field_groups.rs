@@ -0,0 +1,95 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{FieldOrder, ScaffoldOptions, TomlScaffold};
+
+/// Server configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Listen host
+    #[scaffold(group = "Networking")]
+    host: String,
+    /// Listen port
+    #[scaffold(group = "Networking")]
+    port: u16,
+    /// Request limits
+    #[scaffold(group = "Networking")]
+    #[format = "dotted"]
+    limits: Limits,
+    /// Cache size in MiB
+    #[scaffold(group = "Caching")]
+    cache_size: u32,
+    /// Entry time to live in seconds
+    #[scaffold(group = "Caching")]
+    cache_ttl: u64,
+    /// Log level
+    #[scaffold(group = "")]
+    log_level: String,
+    /// Request timeout in seconds
+    #[scaffold(group = "Networking")]
+    timeout: Option<u32>,
+    /// Cache directory
+    #[scaffold(group = "Caching")]
+    cache_dir: String,
+    /// Storage settings
+    storage: Storage,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Limits {
+    rps: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Storage {
+    /// Storage path
+    path: String,
+    /// Backup path
+    #[scaffold(group = "Backups")]
+    backup_path: String,
+    /// Backups to keep
+    #[scaffold(group = "Backups")]
+    backup_count: u32,
+}
+
+fn config() -> Config {
+    Config {
+        name: "app".to_string(),
+        host: "0.0.0.0".to_string(),
+        port: 8080,
+        limits: Limits { rps: 100 },
+        cache_size: 64,
+        cache_ttl: 300,
+        log_level: "info".to_string(),
+        timeout: None,
+        cache_dir: "/var/cache/app".to_string(),
+        storage: Storage {
+            path: "/var/lib/app".to_string(),
+            backup_path: "/var/backups/app".to_string(),
+            backup_count: 7,
+        },
+    }
+}
+
+#[test]
+fn test_field_groups() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("field_groups.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_field_groups_definition_order() {
+    let options = ScaffoldOptions {
+        field_order: FieldOrder::Definition,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("field_groups_definition.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
@@ -0,0 +1,143 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{FieldOrder, ScaffoldOptions, TomlScaffold};
+
+/// Service configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Service name
+    name: String,
+    /// Server settings
+    server: Server,
+    /// Worker threads
+    workers: u32,
+    /// Upstream backends
+    backends: Vec<Backend>,
+    /// Cluster settings
+    cluster: Cluster,
+    /// Schema version of the file
+    #[scaffold(order = -1)]
+    version: u32,
+    /// Enable debug logging
+    debug: bool,
+    /// Log settings
+    logging: Logging,
+    /// Metrics settings
+    metrics: Logging,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Listen host
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Request timeout in seconds
+    timeout: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Backend {
+    /// Backend address
+    addr: String,
+    /// Connection weight
+    weight: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Cluster {
+    /// Cluster name
+    name: String,
+    /// Cluster nodes
+    nodes: Vec<Backend>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Logging {
+    /// Log level
+    level: String,
+    /// Log file
+    #[scaffold(order = 1)]
+    file: String,
+    /// Log format
+    format: String,
+}
+
+fn logging() -> Logging {
+    Logging {
+        level: "info".to_string(),
+        file: "app.log".to_string(),
+        format: "json".to_string(),
+    }
+}
+
+fn config() -> Config {
+    Config {
+        name: "api".to_string(),
+        server: Server {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            timeout: None,
+        },
+        workers: 4,
+        backends: vec![
+            Backend {
+                addr: "10.0.0.2:80".to_string(),
+                weight: None,
+            },
+            Backend {
+                addr: "10.0.0.3:80".to_string(),
+                weight: None,
+            },
+        ],
+        cluster: Cluster {
+            name: "east".to_string(),
+            nodes: vec![Backend {
+                addr: "10.0.1.2:80".to_string(),
+                weight: Some(2),
+            }],
+        },
+        version: 2,
+        debug: false,
+        logging: logging(),
+        metrics: logging(),
+    }
+}
+
+fn scaffold(field_order: FieldOrder) -> String {
+    let options = ScaffoldOptions {
+        field_order,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+    scaffold
+}
+
+#[test]
+fn test_scalars_first_order() {
+    assert_eq!(
+        scaffold(FieldOrder::ScalarsFirst),
+        include_str!("field_order_scalars_first.toml")
+    );
+}
+
+#[test]
+fn test_definition_order() {
+    let scaffold = scaffold(FieldOrder::Definition);
+    assert_eq!(scaffold, include_str!("field_order_definition.toml"));
+
+    // Arrays of tables keep the docs and placeholders of their elements
+    assert!(scaffold.contains("[[backends]]\n# Backend address"));
+    assert!(scaffold.contains("# weight = 0"));
+    assert!(scaffold.contains("[[cluster.nodes]]\n# Backend address"));
+}
+
+#[test]
+fn test_alphabetical_order() {
+    assert_eq!(
+        scaffold(FieldOrder::Alphabetical),
+        include_str!("field_order_alphabetical.toml")
+    );
+}
@@ -0,0 +1,87 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use toml_scaffold::TomlScaffold;
+
+/// Service configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Service<B> {
+    /// Service name
+    name: String,
+    /// Storage backend
+    backend: B,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Disk {
+    /// Data directory
+    path: String,
+    /// Directories mounted read-only
+    #[format = "multiline"]
+    mounts: Vec<String>,
+}
+
+/// Borrowed view of a service
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct View<'a, T> {
+    /// Service name
+    name: &'a str,
+    /// Description
+    description: Cow<'a, str>,
+    /// Tags
+    tags: Vec<T>,
+}
+
+/// Marker types need no `TomlScaffold` bound when overridden
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+#[toml_scaffold(bound = "")]
+struct Tagged<M: 'static> {
+    /// Value
+    value: u32,
+    #[serde(skip)]
+    #[schemars(skip)]
+    #[allow(dead_code)]
+    marker: PhantomData<M>,
+}
+
+struct NotScaffold;
+
+#[test]
+fn test_generic_struct() {
+    let service = Service {
+        name: "store".to_string(),
+        backend: Disk {
+            path: "/var/lib/store".to_string(),
+            mounts: vec!["/etc".to_string()],
+        },
+    };
+    let scaffold = service.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("generics.toml"));
+
+    let deserialized: Service<Disk> = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, service);
+}
+
+#[test]
+fn test_lifetimes() {
+    let name = String::from("store");
+    let view = View {
+        name: &name,
+        description: Cow::Borrowed("Object store"),
+        tags: vec![1, 2],
+    };
+    assert_eq!(
+        view.to_scaffold().unwrap(),
+        "# Borrowed view of a service\n\n# Service name\nname = \"store\"\n# Description\ndescription = \"Object store\"\n# Tags\ntags = [1, 2]\n"
+    );
+}
+
+#[test]
+fn test_bound_override() {
+    let tagged = Tagged::<NotScaffold> {
+        value: 1,
+        marker: PhantomData,
+    };
+    assert!(tagged.to_scaffold().unwrap().contains("value = 1\n"));
+}
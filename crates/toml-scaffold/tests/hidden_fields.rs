@@ -0,0 +1,121 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::TomlScaffold;
+
+/// Server configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug, Default)]
+struct Config {
+    /// Listen address
+    host: String,
+    /// Internal buffer size, only change when told to
+    #[scaffold(hidden)]
+    #[serde(default)]
+    buffer_size: u32,
+    /// Debug token, never set in examples
+    #[scaffold(hidden)]
+    #[serde(default)]
+    debug_token: Option<String>,
+    /// Log level, uncomment to override
+    #[scaffold(commented)]
+    #[serde(default = "default_log_level")]
+    log_level: String,
+    /// TLS settings
+    #[scaffold(commented)]
+    #[serde(default)]
+    tls: Tls,
+    /// Upstream servers
+    upstreams: Vec<Upstream>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug, Default)]
+struct Tls {
+    /// Certificate path
+    cert: String,
+    /// Accepted protocol versions
+    #[format = "multiline"]
+    versions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug, Default)]
+struct Upstream {
+    /// Upstream address
+    addr: String,
+    /// Internal connection id
+    #[scaffold(hidden)]
+    #[serde(default)]
+    id: u64,
+}
+
+fn default_log_level() -> String {
+    "warn".to_string()
+}
+
+#[test]
+fn test_hidden_and_commented_fields() {
+    let config = Config {
+        host: "0.0.0.0".to_string(),
+        buffer_size: 4096,
+        debug_token: None,
+        log_level: "info".to_string(),
+        tls: Tls {
+            cert: "/etc/cert.pem".to_string(),
+            versions: vec!["1.2".to_string(), "1.3".to_string()],
+        },
+        upstreams: vec![Upstream {
+            addr: "10.0.0.1".to_string(),
+            id: 7,
+        }],
+    };
+    let scaffold = config.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("hidden_fields.toml"));
+
+    // The scaffold deserializes as is, hidden and commented fields take their defaults
+    let mut deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized.buffer_size, 0);
+    assert_eq!(deserialized.log_level, "warn");
+    assert_eq!(deserialized.tls, Tls::default());
+    deserialized.upstreams[0].id = 7;
+    assert_eq!(deserialized.upstreams, config.upstreams);
+
+    // Hidden fields can still be set, commented fields uncommented
+    let user_config = format!(
+        "buffer_size = 1\n{}",
+        scaffold.replace("# log_level = \"info\"", "log_level = \"info\"")
+    );
+    let deserialized: Config = toml::from_str(&user_config).unwrap();
+    assert_eq!(deserialized.buffer_size, 1);
+    assert_eq!(deserialized.log_level, "info");
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+struct Renamed {
+    /// Public name
+    display_name: String,
+    /// API secret
+    #[scaffold(hidden)]
+    #[serde(rename = "secret-key", default)]
+    secret_key: String,
+    /// Request timeout
+    #[scaffold(commented)]
+    #[serde(default)]
+    request_timeout: u32,
+    /// Allowed origins
+    #[format = "multiline"]
+    allowed_origins: Vec<String>,
+}
+
+#[test]
+fn test_renamed_hidden_and_commented_fields() {
+    let renamed = Renamed {
+        display_name: "app".to_string(),
+        secret_key: "s".to_string(),
+        request_timeout: 30,
+        allowed_origins: vec!["a".to_string(), "b".to_string()],
+    };
+    let scaffold = renamed.to_scaffold().unwrap();
+    assert_eq!(
+        scaffold,
+        "# Public name\ndisplay-name = \"app\"\n# Request timeout\n# request-timeout = 30\n# Allowed origins\nallowed-origins = [\n  \"a\",\n  \"b\",\n]\n"
+    );
+}
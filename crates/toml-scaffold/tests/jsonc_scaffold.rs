@@ -0,0 +1,132 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::TomlScaffold;
+
+/// Editor settings
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Settings {
+    /// Color theme
+    theme: String,
+    /// Font size in points
+    font_size: u32,
+    /// Optional font family
+    font_family: Option<String>,
+    /// Rulers shown in the editor
+    #[format = "multiline"]
+    rulers: Vec<u32>,
+    /// Formatter configuration
+    formatter: Formatter,
+    /// Per-language overrides
+    languages: Vec<Language>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Formatter {
+    /// Format on save
+    on_save: bool,
+    /// Tab width
+    tab_width: u8,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Language {
+    /// Language identifier
+    name: String,
+    /// File extensions
+    extensions: Vec<String>,
+}
+
+fn settings() -> Settings {
+    Settings {
+        theme: "dark".to_string(),
+        font_size: 14,
+        font_family: None,
+        rulers: vec![80, 100],
+        formatter: Formatter {
+            on_save: true,
+            tab_width: 4,
+        },
+        languages: vec![
+            Language {
+                name: "rust".to_string(),
+                extensions: vec!["rs".to_string()],
+            },
+            Language {
+                name: "toml".to_string(),
+                extensions: vec![],
+            },
+        ],
+    }
+}
+
+/// Convert JSONC to plain JSON by dropping comment lines
+fn to_json(jsonc: &str) -> String {
+    let json: Vec<&str> = jsonc
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("//"))
+        .collect();
+    json.join("\n")
+}
+
+#[test]
+fn test_jsonc_scaffold() {
+    let config = settings();
+    let scaffold = config.to_jsonc_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("jsonc_scaffold.jsonc"));
+
+    let deserialized: Settings = serde_json::from_str(&to_json(&scaffold)).unwrap();
+    assert_eq!(deserialized, config);
+}
+
+#[test]
+fn test_jsonc_uncommented_placeholders() {
+    let scaffold = settings().to_jsonc_scaffold().unwrap();
+    let uncommented = scaffold.replace("// \"font_family\": \"\"", "\"font_family\": \"Fira\"");
+    assert_ne!(uncommented, scaffold);
+
+    let deserialized: Settings = serde_json::from_str(&to_json(&uncommented)).unwrap();
+    assert_eq!(deserialized.font_family.as_deref(), Some("Fira"));
+}
+
+#[test]
+fn test_json5_scaffold() {
+    let scaffold = settings().to_json5_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("jsonc_scaffold.json5"));
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Window {
+    /// Window title
+    title: String,
+    /// Optional width
+    width: Option<u32>,
+    /// Optional height
+    height: Option<u32>,
+}
+
+#[test]
+fn test_jsonc_trailing_placeholders() {
+    let window = Window {
+        title: "main".to_string(),
+        width: None,
+        height: None,
+    };
+    let scaffold = window.to_jsonc_scaffold().unwrap();
+    assert_eq!(
+        scaffold,
+        r#"{
+  // Window title
+  "title": "main"
+  // Optional width
+  // , "width": 0
+  // Optional height
+  // , "height": 0
+}
+"#
+    );
+
+    let uncommented = scaffold.replace("// , \"height\"", ", \"height\"");
+    let deserialized: Window = serde_json::from_str(&to_json(&uncommented)).unwrap();
+    assert_eq!(deserialized.height, Some(0));
+    assert_eq!(deserialized.width, None);
+}
@@ -0,0 +1,114 @@
+use proptest::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml_scaffold::{FieldOrder, ScaffoldOptions, TomlScaffold};
+
+type Inner = BTreeMap<String, u32>;
+
+/// Keys chosen by users, rendered through every key code path
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug, Clone)]
+struct Config {
+    /// Plain map, rendered as a [section]
+    plain: Inner,
+    #[format = "inline"]
+    inline: Inner,
+    #[format = "dotted"]
+    dotted: BTreeMap<String, Inner>,
+    #[format = "dotted-nested"]
+    nested: BTreeMap<String, BTreeMap<String, Inner>>,
+    #[format = "*dotted"]
+    child: BTreeMap<String, Inner>,
+    /// Array of tables, rendered as [[rows]] or columns
+    rows: Vec<Inner>,
+}
+
+fn key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Bare keys
+        "[A-Za-z0-9_-]{1,8}",
+        // Spaces, dots, quotes, escapes and unicode
+        "[ .\"'\\\\#=\\[\\]{}a-zé日本🦀\t\n\u{7f}]{0,6}",
+        any::<String>(),
+    ]
+}
+
+fn inner() -> impl Strategy<Value = Inner> {
+    prop::collection::btree_map(key(), any::<u32>(), 0..4)
+}
+
+fn config() -> impl Strategy<Value = Config> {
+    let row = prop::collection::btree_set(key(), 1..3);
+    (
+        inner(),
+        inner(),
+        prop::collection::btree_map(key(), inner(), 0..3),
+        prop::collection::btree_map(
+            key(),
+            prop::collection::btree_map(key(), inner(), 0..3),
+            0..3,
+        ),
+        prop::collection::btree_map(key(), inner(), 0..3),
+        (row, 1..3usize, any::<u32>()),
+    )
+        .prop_map(
+            |(plain, inline, dotted, nested, child, (row, count, value))| {
+                // Rows share their keys so aligned output renders them as columns
+                let row: Inner = row.into_iter().map(|key| (key, value)).collect();
+                Config {
+                    plain,
+                    inline,
+                    dotted,
+                    nested,
+                    child,
+                    rows: vec![row; count],
+                }
+            },
+        )
+}
+
+fn options() -> impl Strategy<Value = ScaffoldOptions> {
+    (any::<bool>(), any::<bool>(), any::<bool>()).prop_map(|(aligned, trailing, definition)| {
+        ScaffoldOptions {
+            aligned,
+            trailing_comments: trailing,
+            field_order: if definition {
+                FieldOrder::Definition
+            } else {
+                FieldOrder::ScalarsFirst
+            },
+            ..Default::default()
+        }
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_arbitrary_keys_round_trip(config in config(), options in options()) {
+        let scaffold = config.to_scaffold_with(&options).unwrap();
+        let parsed: Result<Config, _> = toml::from_str(&scaffold);
+        prop_assert!(parsed.is_ok(), "{}\n{}", parsed.unwrap_err(), scaffold);
+        prop_assert_eq!(parsed.unwrap(), config);
+    }
+}
+
+#[test]
+fn test_special_keys() {
+    let key = |k: &str| k.to_string();
+    let config = Config {
+        plain: Inner::from([(key("with space"), 1)]),
+        inline: Inner::from([(key("a.b"), 2)]),
+        dotted: BTreeMap::from([(key("ключ"), Inner::from([(key("x\"y"), 3)]))]),
+        nested: BTreeMap::from([(
+            key(""),
+            BTreeMap::from([(key("tab\there"), Inner::from([(key("ok"), 4)]))]),
+        )]),
+        child: BTreeMap::from([(key("日本"), Inner::from([(key("a b"), 5)]))]),
+        rows: vec![Inner::from([(key("🦀"), 6)])],
+    };
+    let scaffold = config.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("key_quoting.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config);
+}
@@ -0,0 +1,152 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{EnvNaming, FieldPath, LoadError, Loader, Source, TomlScaffold};
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Enabled features
+    #[serde(default)]
+    features: Vec<String>,
+    /// Server configuration
+    server: Server,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Bind address
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Optional display name
+    label: Option<String>,
+}
+
+fn path(key: &str) -> FieldPath {
+    key.parse().unwrap()
+}
+
+fn fixture() -> String {
+    concat!(env!("CARGO_MANIFEST_DIR"), "/tests/load.toml").to_string()
+}
+
+#[test]
+fn test_load_file() {
+    let loaded = Loader::<Config>::new().file(fixture()).load().unwrap();
+    assert_eq!(loaded.value.name, "myapp");
+    assert_eq!(loaded.value.server.port, 8080);
+    assert_eq!(loaded.source(&path("server.port")), Some(&Source::File));
+    assert_eq!(loaded.source(&path("features")), Some(&Source::Default));
+}
+
+#[test]
+fn test_load_with_overrides() {
+    let loaded = Loader::<Config>::new()
+        .file(fixture())
+        .env_vars(
+            EnvNaming::new("APP", "__"),
+            [
+                ("APP__SERVER__PORT", "9090"),
+                ("APP__SERVER__LABEL", "1234"),
+                ("APP__FEATURES", "[\"auth\"]"),
+                ("OTHER__NAME", "ignored"),
+            ],
+        )
+        .sets(["server.port=7070", "name = renamed"])
+        .load()
+        .unwrap();
+
+    assert_eq!(
+        loaded.value,
+        Config {
+            name: "renamed".to_string(),
+            features: vec!["auth".to_string()],
+            server: Server {
+                host: "127.0.0.1".to_string(),
+                port: 7070,
+                label: Some("1234".to_string()),
+            },
+        }
+    );
+    assert_eq!(loaded.source(&path("name")), Some(&Source::Cli));
+    assert_eq!(loaded.source(&path("server.host")), Some(&Source::File));
+    assert_eq!(loaded.source(&path("server.port")), Some(&Source::Cli));
+    assert_eq!(
+        loaded.source(&path("server.label")),
+        Some(&Source::Env("APP__SERVER__LABEL".to_string()))
+    );
+    assert_eq!(
+        loaded.source(&path("features")),
+        Some(&Source::Env("APP__FEATURES".to_string()))
+    );
+}
+
+#[test]
+fn test_load_invalid_override() {
+    let result = Loader::<Config>::new()
+        .file(fixture())
+        .set("server.port=fast")
+        .load();
+    assert!(matches!(
+        result,
+        Err(LoadError::Override {
+            source: Source::Cli,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_load_missing_field() {
+    let result = Loader::<Config>::new().document("name = \"x\"").load();
+    assert!(matches!(result, Err(LoadError::Deserialize(_))));
+}
+
+#[test]
+fn test_load_array_element_override() {
+    #[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+    struct Pool {
+        backends: Vec<Backend>,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+    struct Backend {
+        addr: String,
+        weight: u32,
+    }
+
+    let loaded = Loader::<Pool>::new()
+        .document(
+            "[[backends]]\naddr = \"a\"\nweight = 1\n\n[[backends]]\naddr = \"b\"\nweight = 2\n",
+        )
+        .env_vars(
+            EnvNaming::new("APP", "__"),
+            [
+                ("APP__BACKENDS__1__ADDR", "10"),
+                ("APP__BACKENDS__1__WEIGHT", "5"),
+            ],
+        )
+        .load()
+        .unwrap();
+    assert_eq!(loaded.value.backends[0].addr, "a");
+    assert_eq!(loaded.value.backends[1].addr, "10");
+    assert_eq!(loaded.value.backends[1].weight, 5);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_load_env_with_non_unicode_variables() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let invalid = std::ffi::OsStr::from_bytes(b"\xff\xfe");
+    std::env::set_var("TOML_SCAFFOLD_TEST_NON_UNICODE", invalid);
+    std::env::set_var("TOML_SCAFFOLD_TEST__SERVER__PORT", "9191");
+
+    let loaded = Loader::<Config>::new()
+        .file(fixture())
+        .env(EnvNaming::new("TOML_SCAFFOLD_TEST", "__"))
+        .load()
+        .unwrap();
+    assert_eq!(loaded.value.server.port, 9191);
+}
@@ -0,0 +1,137 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{
+    from_str_migrated, FieldPath, Loader, Migration, MigrationError, TomlScaffold,
+};
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[toml_scaffold(migrations = "config_migrations")]
+struct Config {
+    /// Application name
+    name: String,
+    /// Server configuration
+    server: Server,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Bind address
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Request timeout in seconds
+    timeout_secs: u64,
+}
+
+fn config_migrations() -> Vec<Migration> {
+    vec![
+        // v1 moved the flat server settings into [server]
+        Migration::new(1)
+            .rename("app_name", "name")
+            .move_to("host", "server.host")
+            .move_to("port", "server.port")
+            .drop_key("legacy_mode"),
+        // v2 made the timeout unit explicit
+        Migration::new(2)
+            .rename("server.timeout", "timeout_secs")
+            .set_default("server.timeout_secs", 30),
+    ]
+}
+
+fn expected(timeout_secs: u64) -> Config {
+    Config {
+        name: "myapp".to_string(),
+        server: Server {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            timeout_secs,
+        },
+    }
+}
+
+#[test]
+fn test_migrations_scaffold() {
+    let scaffold = expected(30).to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("migrations.toml"));
+
+    let deserialized = from_str_migrated::<Config>(&scaffold).unwrap();
+    assert_eq!(deserialized, expected(30));
+}
+
+#[test]
+fn test_migrations_from_v0() {
+    let config = from_str_migrated::<Config>(include_str!("migrations_v0.toml")).unwrap();
+    assert_eq!(config, expected(30));
+}
+
+#[test]
+fn test_migrations_from_v1() {
+    let config = from_str_migrated::<Config>(include_str!("migrations_v1.toml")).unwrap();
+    assert_eq!(config, expected(10));
+}
+
+#[test]
+fn test_migrations_in_loader() {
+    let loaded = Loader::<Config>::new()
+        .document(include_str!("migrations_v0.toml"))
+        .load()
+        .unwrap();
+    assert_eq!(loaded.value, expected(30));
+}
+
+#[test]
+fn test_migrations_unsupported_version() {
+    let result = from_str_migrated::<Config>("schema_version = 3");
+    assert!(matches!(
+        result,
+        Err(MigrationError::UnsupportedVersion {
+            found: 3,
+            latest: 2
+        })
+    ));
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+#[toml_scaffold(migrations = "config_migrations")]
+struct StrictConfig {
+    name: String,
+    server: StrictServer,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+struct StrictServer {
+    host: String,
+    port: u16,
+    timeout_secs: u64,
+}
+
+#[test]
+fn test_migrations_deny_unknown_fields() {
+    let config = StrictConfig {
+        name: "myapp".to_string(),
+        server: StrictServer {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            timeout_secs: 30,
+        },
+    };
+    let scaffold = config.to_scaffold().unwrap();
+    assert!(scaffold.contains("\nschema_version = 2\n"));
+    assert_eq!(
+        from_str_migrated::<StrictConfig>(&scaffold).unwrap(),
+        config
+    );
+
+    let upgraded = from_str_migrated::<StrictConfig>(include_str!("migrations_v1.toml")).unwrap();
+    assert_eq!(upgraded.server.timeout_secs, 10);
+
+    let loaded = Loader::<StrictConfig>::new()
+        .document(&scaffold)
+        .load()
+        .unwrap();
+    assert_eq!(loaded.value, config);
+    let version = FieldPath::from_vec(vec!["schema_version".to_string()]);
+    assert_eq!(loaded.source(&version), None);
+}
@@ -0,0 +1,87 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{FieldPath, TomlScaffold};
+
+/// Cluster configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Cluster {
+    /// Listen port
+    port: Port,
+    /// Cluster members
+    hosts: Hosts,
+    /// Seed nodes
+    seeds: Seeds,
+    /// Connection pool
+    pool: Pool,
+    /// Replica limits
+    #[format = "multiline"]
+    limits: Limits,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Port(u16);
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Hosts(Vec<Host>);
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Host {
+    /// Host name
+    name: String,
+    /// Labels attached to the host
+    #[format = "multiline"]
+    labels: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Seeds(#[format = "multiline"] Vec<String>);
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[format = "inline"]
+struct Pool(PoolSettings);
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct PoolSettings {
+    min: u32,
+    max: u32,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Limits(#[format = "inline"] Vec<u32>);
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Pair(u32, u32);
+
+#[test]
+fn test_newtype_fields() {
+    let cluster = Cluster {
+        port: Port(7000),
+        hosts: Hosts(vec![Host {
+            name: "node-1".to_string(),
+            labels: vec!["zone-a".to_string(), "ssd".to_string()],
+        }]),
+        seeds: Seeds(vec!["node-1:7000".to_string()]),
+        pool: Pool(PoolSettings { min: 1, max: 8 }),
+        limits: Limits(vec![1, 3]),
+    };
+    let scaffold = cluster.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("newtype_fields.toml"));
+
+    let deserialized: Cluster = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, cluster);
+}
+
+#[test]
+fn test_field_format_overrides_newtype_format() {
+    let preferences = Cluster::format_preferences();
+    let path =
+        |segments: &[&str]| FieldPath::from_vec(segments.iter().map(|s| s.to_string()).collect());
+    assert_eq!(preferences[&path(&["limits"])], "multiline");
+    assert_eq!(preferences[&path(&["pool"])], "inline");
+    assert_eq!(preferences[&path(&["hosts", "labels"])], "multiline");
+}
+
+#[test]
+fn test_tuple_struct_has_no_preferences() {
+    assert!(Pair::format_preferences().is_empty());
+}
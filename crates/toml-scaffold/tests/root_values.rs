@@ -0,0 +1,89 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml_scaffold::{ScaffoldOptions, TomlScaffold};
+
+/// Upstream server
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Server address
+    host: String,
+    /// Connection weight
+    weight: u32,
+    /// Health check settings
+    #[format = "inline"]
+    health: Health,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Health {
+    path: String,
+    interval: u32,
+}
+
+/// Load balancer pool
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Pool(Server);
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Servers {
+    servers: Vec<Server>,
+}
+
+fn server(host: &str, weight: u32) -> Server {
+    Server {
+        host: host.to_string(),
+        weight,
+        health: Health {
+            path: "/health".to_string(),
+            interval: 10,
+        },
+    }
+}
+
+#[test]
+fn test_vec_root() {
+    let servers = vec![server("10.0.0.1", 1), server("10.0.0.2", 2)];
+    let options = ScaffoldOptions {
+        root_array_key: "servers".to_string(),
+        ..Default::default()
+    };
+    let scaffold = servers.to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("root_values_vec.toml"));
+
+    let deserialized: Servers = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized.servers, servers);
+
+    // Default key
+    let scaffold = servers.to_scaffold().unwrap();
+    assert!(scaffold.contains("[[items]]\n"));
+}
+
+#[test]
+fn test_newtype_root() {
+    let pool = Pool(server("10.0.0.1", 1));
+    let scaffold = pool.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("root_values_newtype.toml"));
+
+    let deserialized: Pool = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, pool);
+}
+
+#[test]
+fn test_map_root() {
+    let mut servers = BTreeMap::new();
+    servers.insert("primary".to_string(), server("10.0.0.1", 1));
+    let scaffold = servers.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("root_values_map.toml"));
+
+    let deserialized: BTreeMap<String, Server> = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, servers);
+}
+
+#[test]
+fn test_unrepresentable_root() {
+    let error = 42u32.to_scaffold().unwrap_err();
+    assert!(error.to_string().contains("integer root"), "{}", error);
+    assert!("text".to_string().to_scaffold().is_err());
+    assert!(None::<Server>.to_scaffold().is_err());
+}
@@ -0,0 +1,78 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{ScaffoldOptions, TomlScaffold};
+
+/// # Worker configuration
+///
+/// See [`Worker`] for the runtime behavior.
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Queue backend, see [`Backend`] for the supported values:
+    ///
+    /// - `redis` - uses [Redis](https://redis.io)
+    /// - `memory` - for **tests** only
+    queue: String,
+    /// Number of threads, defaults to [`num_cpus::get`]
+    #[scaffold(doc = "Number of worker threads")]
+    threads: u32,
+    /// Retry policy
+    retry: Retry,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Retry {
+    /// Maximum attempts, `0` disables retries
+    #[scaffold(doc = "Maximum attempts\nSet to 0 to disable retries")]
+    attempts: u32,
+    /// Delay between attempts in `ms`, *doubled* after each _failed_ attempt
+    delay_ms: u64,
+}
+
+fn config() -> Config {
+    Config {
+        queue: "redis".to_string(),
+        threads: 4,
+        retry: Retry {
+            attempts: 3,
+            delay_ms: 500,
+        },
+    }
+}
+
+#[test]
+fn test_scaffold_doc_overrides() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert!(scaffold.contains("# Number of worker threads\nthreads = 4\n"));
+    assert!(scaffold.contains("# Maximum attempts\n# Set to 0 to disable retries\nattempts = 3\n"));
+    assert!(scaffold.contains("# Queue backend, see [`Backend`]"));
+}
+
+#[test]
+fn test_strip_markdown() {
+    let options = ScaffoldOptions {
+        strip_markdown: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("scaffold_doc.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_strip_markdown_trailing_comments() {
+    let options = ScaffoldOptions {
+        strip_markdown: true,
+        trailing_comments: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert!(
+        scaffold.contains(
+            "delay_ms = 500  # Delay between attempts in ms, doubled after each failed attempt\n"
+        ),
+        "{}",
+        scaffold
+    );
+}
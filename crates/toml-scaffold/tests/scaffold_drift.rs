@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use toml_scaffold::{assert_scaffold_up_to_date, check_scaffold, DriftError, TomlScaffold};
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Config {
+    /// Server host address
+    host: String,
+    /// Server port
+    port: u16,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 8080,
+        }
+    }
+}
+
+#[test]
+fn test_scaffold_up_to_date() {
+    assert_scaffold_up_to_date!(Config::default(), "tests/scaffold_drift.toml");
+}
+
+#[test]
+fn test_scaffold_drift_reports_diff() {
+    let config = Config {
+        port: 9090,
+        ..Config::default()
+    };
+    // Compare against a copy, only the real fixture may be blessed
+    let path = std::env::temp_dir().join(format!(
+        "toml-scaffold-drift-test-{}.toml",
+        std::process::id()
+    ));
+    std::fs::write(&path, include_str!("scaffold_drift.toml")).unwrap();
+    let result = check_scaffold(&config, &path);
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    match result {
+        Err(DriftError::OutOfDate { diff, .. }) => {
+            assert!(diff.contains("-port = 8080\n+port = 9090\n"))
+        }
+        // `TOML_SCAFFOLD_BLESS` rewrites the copy instead
+        Ok(()) => assert_eq!(written, config.to_scaffold().unwrap()),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,50 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml_scaffold::TomlScaffold;
+
+/// Build configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Target name
+    name: String,
+    /// Extra compiler flags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    flags: Vec<String>,
+    /// Environment variables for the build
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    env: BTreeMap<String, String>,
+    /// Output settings
+    output: Output,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Output {
+    /// Output directory
+    dir: String,
+    /// Strip debug symbols
+    #[serde(default, skip_serializing_if = "is_false")]
+    strip: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[test]
+fn test_skipped_fields_as_placeholders() {
+    let config = Config {
+        name: "app".to_string(),
+        flags: Vec::new(),
+        env: BTreeMap::new(),
+        output: Output {
+            dir: "target".to_string(),
+            strip: false,
+        },
+    };
+    let scaffold = config.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("skip_serializing_if.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config);
+}
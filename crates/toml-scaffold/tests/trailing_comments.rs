@@ -0,0 +1,77 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::{ScaffoldOptions, TomlScaffold};
+
+/// Proxy configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Worker threads
+    #[scaffold(trailing_comments)]
+    workers: u32,
+    /// Server settings
+    #[scaffold(trailing_comments)]
+    server: Server,
+    /// Cache settings
+    cache: Cache,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Listen host
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Allowed origins, matched in order.
+    ///
+    /// Wildcards are supported.
+    origins: Vec<String>,
+    /// Upstream peers
+    #[format = "multiline"]
+    peers: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Cache {
+    /// Cache size in MiB
+    size: u32,
+    /// Entry time to live in seconds
+    ttl: u64,
+}
+
+fn config() -> Config {
+    Config {
+        name: "proxy".to_string(),
+        workers: 4,
+        server: Server {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            origins: vec!["*".to_string()],
+            peers: vec!["10.0.0.2".to_string(), "10.0.0.3".to_string()],
+        },
+        cache: Cache { size: 64, ttl: 300 },
+    }
+}
+
+#[test]
+fn test_trailing_comments_attribute() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("trailing_comments.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_trailing_comments_option() {
+    let options = ScaffoldOptions {
+        trailing_comments: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("trailing_comments_all.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
@@ -0,0 +1,100 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml_scaffold::{ScaffoldOptions, TomlScaffold};
+
+/// Cache configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Request timeout in seconds
+    timeout: u32,
+    /// Entry lifetime in seconds
+    #[scaffold(deprecated = "use `timeout` instead")]
+    ttl: u32,
+    /// Cache size ratio
+    ratio: f64,
+    /// Peer addresses
+    peers: Vec<String>,
+    /// Eviction mode
+    mode: Mode,
+    /// Optional label
+    label: Option<String>,
+    server: Server,
+    /// Storage backends by name
+    backends: BTreeMap<String, Backend>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Lru,
+    Fifo,
+}
+
+/// Server settings
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Server {
+    /// Listen port
+    port: u16,
+    tls: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Backend {
+    /// Backend URL
+    url: String,
+}
+
+fn config() -> Config {
+    Config {
+        timeout: 30,
+        ttl: 60,
+        ratio: 0.5,
+        peers: vec!["10.0.0.2".to_string()],
+        mode: Mode::Lru,
+        label: None,
+        server: Server {
+            port: 8080,
+            tls: false,
+        },
+        backends: BTreeMap::from([(
+            "main".to_string(),
+            Backend {
+                url: "s3://bucket".to_string(),
+            },
+        )]),
+    }
+}
+
+#[test]
+fn test_type_annotations() {
+    let options = ScaffoldOptions {
+        annotate_types: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("type_annotations.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
+
+#[test]
+fn test_type_annotations_off_by_default() {
+    let scaffold = config().to_scaffold().unwrap();
+    assert!(!scaffold.contains("# type:"));
+}
+
+#[test]
+fn test_type_annotations_trailing_comments() {
+    let options = ScaffoldOptions {
+        annotate_types: true,
+        trailing_comments: true,
+        ..Default::default()
+    };
+    let scaffold = config().to_scaffold_with(&options).unwrap();
+    assert_eq!(scaffold, include_str!("type_annotations_trailing.toml"));
+
+    let deserialized: Config = toml::from_str(&scaffold).unwrap();
+    assert_eq!(deserialized, config());
+}
@@ -0,0 +1,79 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use toml_scaffold::TomlScaffold;
+
+/// Cache configuration
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Config {
+    /// Cache name
+    name: String,
+    /// Listen port
+    port: Option<u16>,
+    /// Eviction mode
+    mode: Option<Mode>,
+    /// Peer addresses
+    #[scaffold(example = ["10.0.0.2:7000", "10.0.0.3:7000"])]
+    peers: Option<Vec<String>>,
+    /// Memory limit in megabytes
+    #[scaffold(example = 512)]
+    memory_mb: Option<u32>,
+    /// Data directory
+    #[schemars(example = "/var/lib/cache")]
+    data_dir: Option<String>,
+    /// Persistence settings
+    persistence: Option<Persistence>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Lru,
+    Lfu,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, TomlScaffold, PartialEq, Debug)]
+struct Persistence {
+    /// Snapshot interval in seconds
+    interval: u64,
+    /// Compress snapshots
+    compress: bool,
+}
+
+#[test]
+fn test_typed_placeholders() {
+    let config = Config {
+        name: "cache".to_string(),
+        port: None,
+        mode: None,
+        peers: None,
+        memory_mb: None,
+        data_dir: None,
+        persistence: None,
+    };
+    let scaffold = config.to_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("typed_placeholders.toml"));
+
+    // Uncommenting every placeholder gives a working config
+    let uncommented: String = scaffold
+        .lines()
+        .map(|line| {
+            line.strip_prefix("# ")
+                .filter(|l| l.contains(" = "))
+                .unwrap_or(line)
+        })
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let deserialized: Config = toml::from_str(&uncommented).unwrap();
+    assert_eq!(deserialized.port, Some(0));
+    assert_eq!(deserialized.mode, Some(Mode::Lru));
+    assert_eq!(deserialized.memory_mb, Some(512));
+    assert_eq!(deserialized.peers.unwrap().len(), 2);
+    assert_eq!(deserialized.data_dir.as_deref(), Some("/var/lib/cache"));
+    assert_eq!(
+        deserialized.persistence,
+        Some(Persistence {
+            interval: 0,
+            compress: false
+        })
+    );
+}
@@ -0,0 +1,68 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use toml_scaffold::TomlScaffold;
+
+/// Application configuration
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Config {
+    /// Application name
+    name: String,
+    /// Enabled features
+    features: Vec<String>,
+    /// Optional worker count
+    workers: Option<u32>,
+    /// Server configuration
+    server: Server,
+    /// Inline metadata
+    #[format = "inline"]
+    metadata: HashMap<String, String>,
+    /// Backend list
+    backends: Vec<Backend>,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Server {
+    /// Bind address
+    host: String,
+    /// Listen port
+    port: u16,
+    /// Ports to try when the listen port is taken
+    #[format = "multiline"]
+    fallback_ports: Vec<u16>,
+}
+
+#[derive(Serialize, JsonSchema, TomlScaffold)]
+struct Backend {
+    /// Backend URL
+    url: String,
+    /// Relative weight
+    weight: f64,
+}
+
+#[test]
+fn test_yaml_scaffold() {
+    let config = Config {
+        name: "myapp".to_string(),
+        features: vec!["auth".to_string(), "metrics".to_string()],
+        workers: None,
+        server: Server {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            fallback_ports: vec![8081, 8082],
+        },
+        metadata: HashMap::from([("team".to_string(), "infra".to_string())]),
+        backends: vec![
+            Backend {
+                url: "http://a.internal".to_string(),
+                weight: 1.0,
+            },
+            Backend {
+                url: "http://b.internal".to_string(),
+                weight: 0.5,
+            },
+        ],
+    };
+    let scaffold = config.to_yaml_scaffold().unwrap();
+    assert_eq!(scaffold, include_str!("yaml_scaffold.yaml"));
+}